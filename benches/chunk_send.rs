@@ -0,0 +1,82 @@
+//! Compares writing a MAP_CHUNK packet to a socket one byte at a time, as
+//! the connection writer used to, with encoding it into a buffer first.
+//!
+//! Run with `cargo +nightly bench`.
+
+#![feature(test)]
+
+extern crate test;
+
+use irox_bits::MutBits;
+use oxidized_alpha::{
+	bytes::ByteWriter,
+	generator::{AlphaGenerator, WorldGenerator},
+	packets::ClientboundPacket,
+};
+use std::{
+	io::{self, Write},
+	net::{TcpListener, TcpStream},
+};
+use test::Bencher;
+
+/// Connects to a local socket whose other end is drained by a thread.
+fn sink() -> TcpStream {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+	std::thread::spawn(move || {
+		let (mut stream, _) = listener.accept().unwrap();
+		io::copy(&mut stream, &mut io::sink()).unwrap();
+	});
+
+	TcpStream::connect(address).unwrap()
+}
+
+fn chunk_packet() -> ClientboundPacket {
+	AlphaGenerator::new(0).generate(0, 0).to_packet()
+}
+
+fn encoded_length(packet: &ClientboundPacket) -> u64 {
+	let mut buffer = ByteWriter::new();
+	packet.encode(&mut buffer).unwrap();
+
+	buffer.as_bytes().len() as u64
+}
+
+/// Writes and flushes every byte on its own.
+struct Unbuffered<'a>(&'a mut TcpStream);
+
+impl MutBits for Unbuffered<'_> {
+	fn write_u8(&mut self, val: u8) -> Result<(), irox_bits::Error> {
+		let failed = |_| {
+			irox_bits::Error::new(
+				irox_bits::BitsErrorKind::BrokenPipe,
+				"Failed to write byte",
+			)
+		};
+		self.0.write_all(&[val]).map_err(failed)?;
+		self.0.flush().map_err(failed)
+	}
+}
+
+#[bench]
+fn send_chunk_per_byte(bencher: &mut Bencher) {
+	let packet = chunk_packet();
+	let mut stream = sink();
+
+	bencher.bytes = encoded_length(&packet);
+	bencher.iter(|| packet.encode(&mut Unbuffered(&mut stream)).unwrap());
+}
+
+#[bench]
+fn send_chunk_buffered(bencher: &mut Bencher) {
+	let packet = chunk_packet();
+	let mut stream = sink();
+	let mut buffer = ByteWriter::new();
+
+	bencher.bytes = encoded_length(&packet);
+	bencher.iter(|| {
+		buffer.clear();
+		packet.encode(&mut buffer).unwrap();
+		stream.write_all(buffer.as_bytes()).unwrap();
+	});
+}
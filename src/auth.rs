@@ -0,0 +1,239 @@
+//! Verifying usernames with a session server, for online mode.
+//!
+//! When a player joins an online mode server, their client tells the
+//! session server which server hash it is joining. The server then asks the
+//! session server whether that player really did, with a `checkserver`
+//! request answered by `YES` or `NO`.
+
+use crate::{Result, SessionServerFailedSnafu};
+use snafu::ensure;
+use std::{
+	io::{self, ErrorKind, Read, Write},
+	net::{TcpStream, ToSocketAddrs},
+	str::FromStr,
+	time::Duration,
+};
+
+/// Where the Alpha server sent its `checkserver` requests.
+pub const DEFAULT_SESSION_SERVER: &str =
+	"http://www.minecraft.net/game/checkserver.jsp";
+
+/// How long to wait on the session server before giving up on a login.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The `checkserver` endpoint of a session server, parsed from a plain
+/// `http://host[:port]/path` URL. HTTPS is not supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionServer {
+	/// The host and port as written in the URL, for the `Host` header.
+	authority: String,
+	host: String,
+	port: u16,
+	path: String,
+}
+
+impl FromStr for SessionServer {
+	type Err = ();
+
+	fn from_str(url: &str) -> Result<Self, ()> {
+		let rest = url.strip_prefix("http://").ok_or(())?;
+		let (authority, path) =
+			rest.split_at(rest.find('/').unwrap_or(rest.len()));
+		let (host, port) = match authority.rsplit_once(':') {
+			Some((host, port)) if !port.ends_with(']') => {
+				(host, port.parse().map_err(|_| ())?)
+			}
+			_ => (authority, 80),
+		};
+		let host = host.trim_start_matches('[').trim_end_matches(']');
+		if host.is_empty() {
+			return Err(());
+		}
+
+		Ok(Self {
+			authority: authority.into(),
+			host: host.into(),
+			port,
+			path: if path.is_empty() { "/" } else { path }.into(),
+		})
+	}
+}
+
+impl SessionServer {
+	/// Asks whether `username` has joined the server that handed out
+	/// `server_hash`. Blocks until the session server answers, so it should
+	/// not be called from the tick loop.
+	pub fn check(&self, username: &str, server_hash: &str) -> Result<bool> {
+		let separator = if self.path.contains('?') { '&' } else { '?' };
+		let request = format!(
+			"GET {}{separator}user={}&serverId={} HTTP/1.0\r\n\
+			 Host: {}\r\n\
+			 Connection: close\r\n\r\n",
+			self.path,
+			encode(username),
+			encode(server_hash),
+			self.authority,
+		);
+
+		let mut stream = self.connect()?;
+		stream.set_read_timeout(Some(TIMEOUT))?;
+		stream.set_write_timeout(Some(TIMEOUT))?;
+		stream.write_all(request.as_bytes())?;
+		let mut response = Vec::new();
+		stream.read_to_end(&mut response)?;
+
+		let response = String::from_utf8_lossy(&response);
+		let (head, body) =
+			response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+		let status = head.lines().next().unwrap_or_default();
+		ensure!(
+			status.split_whitespace().nth(1) == Some("200"),
+			SessionServerFailedSnafu { status }
+		);
+
+		Ok(body.trim() == "YES")
+	}
+
+	/// Connects to the first address the host resolves to that answers
+	/// within the timeout.
+	fn connect(&self) -> Result<TcpStream> {
+		let mut error = None;
+		for address in (self.host.as_str(), self.port).to_socket_addrs()? {
+			match TcpStream::connect_timeout(&address, TIMEOUT) {
+				Ok(stream) => return Ok(stream),
+				Err(err) => error = Some(err),
+			}
+		}
+
+		Err(error
+			.unwrap_or_else(|| {
+				io::Error::new(
+					ErrorKind::NotFound,
+					format!("{} has no addresses", self.host),
+				)
+			})
+			.into())
+	}
+}
+
+/// Percent-encodes everything but the unreserved characters of a URL.
+fn encode(text: &str) -> String {
+	let mut encoded = String::with_capacity(text.len());
+	for byte in text.bytes() {
+		if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+			encoded.push(byte as char);
+		} else {
+			encoded.push_str(&format!("%{byte:02X}"));
+		}
+	}
+
+	encoded
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Error;
+	use std::{net::TcpListener, thread};
+
+	#[test]
+	fn parses_session_server_urls() {
+		let server: SessionServer =
+			"http://example.com:8080/game/checkserver.jsp"
+				.parse()
+				.unwrap();
+		assert_eq!(
+			server,
+			SessionServer {
+				authority: "example.com:8080".into(),
+				host: "example.com".into(),
+				port: 8080,
+				path: "/game/checkserver.jsp".into(),
+			}
+		);
+
+		let server: SessionServer = "http://example.com".parse().unwrap();
+		assert_eq!((server.port, server.path.as_str()), (80, "/"));
+
+		let server: SessionServer = "http://[::1]/check".parse().unwrap();
+		assert_eq!((server.host.as_str(), server.port), ("::1", 80));
+		let server: SessionServer = "http://[::1]:81/check".parse().unwrap();
+		assert_eq!((server.host.as_str(), server.port), ("::1", 81));
+
+		for url in [
+			"https://example.com/check",
+			"ftp://example.com",
+			"example.com/check",
+			"http://",
+			"http://:80/check",
+			"http://example.com:http/check",
+			"http://example.com:70000/check",
+		] {
+			assert!(url.parse::<SessionServer>().is_err(), "{url}");
+		}
+	}
+
+	#[test]
+	fn percent_encodes_query_values() {
+		assert_eq!(encode("Notch_1.-~"), "Notch_1.-~");
+		assert_eq!(encode("a b&c=d"), "a%20b%26c%3Dd");
+		assert_eq!(encode("Å"), "%C3%85");
+	}
+
+	/// Answers a single request with `response`, returning the request.
+	fn serve(
+		response: impl Into<String>,
+	) -> (SessionServer, thread::JoinHandle<String>) {
+		let response = response.into();
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let port = listener.local_addr().unwrap().port();
+		let handle = thread::spawn(move || {
+			let (mut stream, _) = listener.accept().unwrap();
+			let mut request = Vec::new();
+			let mut buffer = [0; 1024];
+			while !request.ends_with(b"\r\n\r\n") {
+				let read = stream.read(&mut buffer).unwrap();
+				request.extend_from_slice(&buffer[..read]);
+			}
+			stream.write_all(response.as_bytes()).unwrap();
+			String::from_utf8(request).unwrap()
+		});
+		let url = format!("http://127.0.0.1:{port}/check?v=1");
+
+		(url.parse().unwrap(), handle)
+	}
+
+	#[test]
+	fn asks_the_session_server_about_the_login() {
+		let (server, handle) = serve("HTTP/1.0 200 OK\r\n\r\nYES\n");
+		assert!(server.check("st eve", "1a2b").unwrap());
+
+		let request = handle.join().unwrap();
+		let port = server.port;
+		assert_eq!(
+			request,
+			format!(
+				"GET /check?v=1&user=st%20eve&serverId=1a2b HTTP/1.0\r\n\
+				 Host: 127.0.0.1:{port}\r\n\
+				 Connection: close\r\n\r\n"
+			)
+		);
+	}
+
+	#[test]
+	fn only_accepts_a_yes() {
+		for reply in ["NO", "", "yes", "YES please"] {
+			let response = format!("HTTP/1.0 200 OK\r\n\r\n{reply}");
+			let (server, handle) = serve(response);
+			assert!(!server.check("steve", "1a2b").unwrap(), "{reply:?}");
+			handle.join().unwrap();
+		}
+
+		let (server, handle) = serve("HTTP/1.0 503 Unavailable\r\n\r\nYES");
+		assert!(matches!(
+			server.check("steve", "1a2b"),
+			Err(Error::SessionServerFailed { .. })
+		));
+		handle.join().unwrap();
+	}
+}
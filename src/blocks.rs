@@ -0,0 +1,446 @@
+//! Block ids as they appear on the wire and in chunk files, and what the
+//! Alpha server knows about each block.
+
+use crate::{
+	items::{self, Tool, ToolKind},
+	random::JavaRandom,
+	ItemStack,
+};
+
+pub const AIR: u8 = 0;
+pub const STONE: u8 = 1;
+pub const GRASS: u8 = 2;
+pub const DIRT: u8 = 3;
+pub const COBBLESTONE: u8 = 4;
+pub const PLANKS: u8 = 5;
+pub const SAPLING: u8 = 6;
+pub const BEDROCK: u8 = 7;
+pub const FLOWING_WATER: u8 = 8;
+pub const WATER: u8 = 9;
+pub const FLOWING_LAVA: u8 = 10;
+pub const LAVA: u8 = 11;
+pub const SAND: u8 = 12;
+pub const GRAVEL: u8 = 13;
+pub const GOLD_ORE: u8 = 14;
+pub const IRON_ORE: u8 = 15;
+pub const COAL_ORE: u8 = 16;
+pub const LOG: u8 = 17;
+pub const LEAVES: u8 = 18;
+pub const SPONGE: u8 = 19;
+pub const GLASS: u8 = 20;
+pub const CLOTH: u8 = 35;
+pub const YELLOW_FLOWER: u8 = 37;
+pub const RED_ROSE: u8 = 38;
+pub const BROWN_MUSHROOM: u8 = 39;
+pub const RED_MUSHROOM: u8 = 40;
+pub const GOLD_BLOCK: u8 = 41;
+pub const IRON_BLOCK: u8 = 42;
+pub const DOUBLE_SLAB: u8 = 43;
+pub const SLAB: u8 = 44;
+pub const BRICKS: u8 = 45;
+pub const TNT: u8 = 46;
+pub const BOOKSHELF: u8 = 47;
+pub const MOSSY_COBBLESTONE: u8 = 48;
+pub const OBSIDIAN: u8 = 49;
+pub const TORCH: u8 = 50;
+pub const FIRE: u8 = 51;
+pub const MOB_SPAWNER: u8 = 52;
+pub const WOODEN_STAIRS: u8 = 53;
+pub const CHEST: u8 = 54;
+pub const REDSTONE_WIRE: u8 = 55;
+pub const DIAMOND_ORE: u8 = 56;
+pub const DIAMOND_BLOCK: u8 = 57;
+pub const WORKBENCH: u8 = 58;
+pub const CROPS: u8 = 59;
+pub const FARMLAND: u8 = 60;
+pub const FURNACE: u8 = 61;
+pub const LIT_FURNACE: u8 = 62;
+pub const SIGN_POST: u8 = 63;
+pub const WOODEN_DOOR: u8 = 64;
+pub const LADDER: u8 = 65;
+pub const RAILS: u8 = 66;
+pub const COBBLESTONE_STAIRS: u8 = 67;
+pub const WALL_SIGN: u8 = 68;
+pub const LEVER: u8 = 69;
+pub const STONE_PRESSURE_PLATE: u8 = 70;
+pub const IRON_DOOR: u8 = 71;
+pub const WOODEN_PRESSURE_PLATE: u8 = 72;
+pub const REDSTONE_ORE: u8 = 73;
+pub const LIT_REDSTONE_ORE: u8 = 74;
+pub const UNLIT_REDSTONE_TORCH: u8 = 75;
+pub const REDSTONE_TORCH: u8 = 76;
+pub const STONE_BUTTON: u8 = 77;
+pub const SNOW_LAYER: u8 = 78;
+pub const ICE: u8 = 79;
+pub const SNOW: u8 = 80;
+pub const CACTUS: u8 = 81;
+pub const CLAY: u8 = 82;
+pub const REED: u8 = 83;
+pub const JUKEBOX: u8 = 84;
+pub const FENCE: u8 = 85;
+pub const PUMPKIN: u8 = 86;
+pub const NETHERRACK: u8 = 87;
+pub const SOUL_SAND: u8 = 88;
+pub const GLOWSTONE: u8 = 89;
+pub const PORTAL: u8 = 90;
+pub const JACK_O_LANTERN: u8 = 91;
+
+/// What breaking a block leaves behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drop {
+	Nothing,
+	/// One of the block itself.
+	Itself,
+	/// Between `min` and `max` of an item.
+	Item {
+		id: i16,
+		min: u8,
+		max: u8,
+	},
+	/// One of an item one time in `one_in`, and one of `otherwise`, if
+	/// anything, the rest of the time.
+	Rare {
+		id: i16,
+		one_in: i32,
+		otherwise: Option<i16>,
+	},
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Block {
+	pub id: u8,
+	pub name: &'static str,
+	/// How long the block takes to break. Negative for blocks that cannot
+	/// be broken at all.
+	pub hardness: f32,
+	/// The tool, and the lowest tier of it, a block has to be broken with
+	/// to drop anything.
+	pub harvest_tool: Option<Tool>,
+	pub drop: Drop,
+	/// The light level the block gives off, up to 15.
+	pub light_emission: u8,
+	/// How much light passing through the block is dimmed by, where 15
+	/// stops it entirely.
+	pub light_opacity: u8,
+	/// Whether entities collide with the block. Placing such a block where
+	/// a player stands would trap them inside it.
+	pub collides: bool,
+	/// Whether placing a block may overwrite this one.
+	pub replaceable: bool,
+}
+
+impl Block {
+	/// A solid, opaque block that drops itself.
+	const fn new(id: u8, name: &'static str, hardness: f32) -> Self {
+		Self {
+			id,
+			name,
+			hardness,
+			harvest_tool: None,
+			drop: Drop::Itself,
+			light_emission: 0,
+			light_opacity: 15,
+			collides: true,
+			replaceable: false,
+		}
+	}
+
+	/// Lets light through without dimming it.
+	const fn transparent(self) -> Self {
+		self.opacity(0)
+	}
+
+	/// Lets light and entities through.
+	const fn passable(self) -> Self {
+		Self {
+			collides: false,
+			..self.transparent()
+		}
+	}
+
+	const fn replaceable(self) -> Self {
+		Self {
+			replaceable: true,
+			..self
+		}
+	}
+
+	const fn opacity(self, light_opacity: u8) -> Self {
+		Self {
+			light_opacity,
+			..self
+		}
+	}
+
+	const fn light(self, light_emission: u8) -> Self {
+		Self {
+			light_emission,
+			..self
+		}
+	}
+
+	const fn drops(self, drop: Drop) -> Self {
+		Self { drop, ..self }
+	}
+
+	const fn drops_item(self, id: i16) -> Self {
+		self.drops(Drop::Item { id, min: 1, max: 1 })
+	}
+
+	const fn needs(self, kind: ToolKind, tier: u8) -> Self {
+		Self {
+			harvest_tool: Some(Tool { kind, tier }),
+			..self
+		}
+	}
+
+	/// A block that only drops something when broken with a pickaxe.
+	const fn rock(id: u8, name: &'static str, hardness: f32) -> Self {
+		Self::new(id, name, hardness).needs(ToolKind::Pickaxe, 0)
+	}
+
+	/// What breaking the block with `tool` leaves behind, if anything.
+	pub fn drops_for(
+		&self,
+		tool: Option<Tool>,
+		random: &mut JavaRandom,
+	) -> Option<ItemStack> {
+		if let Some(needed) = self.harvest_tool {
+			let harvests = tool.is_some_and(|tool| {
+				tool.kind == needed.kind && tool.tier >= needed.tier
+			});
+			if !harvests {
+				return None;
+			}
+		}
+
+		let (id, count) = match self.drop {
+			Drop::Nothing => return None,
+			Drop::Itself => (self.id as i16, 1),
+			Drop::Item { id, min, max } => {
+				let extra = random.next_int((max - min) as i32 + 1);
+				(id, min + extra as u8)
+			}
+			Drop::Rare {
+				id,
+				one_in,
+				otherwise,
+			} => {
+				if random.next_int(one_in) == 0 {
+					(id, 1)
+				} else {
+					(otherwise?, 1)
+				}
+			}
+		};
+
+		Some(ItemStack {
+			id,
+			count,
+			damage: 0,
+		})
+	}
+}
+
+/// Every Alpha block, ordered by id.
+static BLOCKS: &[Block] = &[
+	Block::new(AIR, "Air", 0.0)
+		.passable()
+		.replaceable()
+		.drops(Drop::Nothing),
+	Block::rock(STONE, "Stone", 1.5).drops_item(COBBLESTONE as i16),
+	Block::new(GRASS, "Grass", 0.6).drops_item(DIRT as i16),
+	Block::new(DIRT, "Dirt", 0.5),
+	Block::rock(COBBLESTONE, "Cobblestone", 2.0),
+	Block::new(PLANKS, "Wooden Planks", 2.0),
+	Block::new(SAPLING, "Sapling", 0.0).passable(),
+	Block::new(BEDROCK, "Bedrock", -1.0),
+	Block::new(FLOWING_WATER, "Flowing Water", 100.0)
+		.passable()
+		.opacity(3)
+		.replaceable()
+		.drops(Drop::Nothing),
+	Block::new(WATER, "Water", 100.0)
+		.passable()
+		.opacity(3)
+		.replaceable()
+		.drops(Drop::Nothing),
+	Block::new(FLOWING_LAVA, "Flowing Lava", 0.0)
+		.passable()
+		.opacity(15)
+		.light(15)
+		.replaceable()
+		.drops(Drop::Nothing),
+	Block::new(LAVA, "Lava", 100.0)
+		.passable()
+		.opacity(15)
+		.light(15)
+		.replaceable()
+		.drops(Drop::Nothing),
+	Block::new(SAND, "Sand", 0.5),
+	Block::new(GRAVEL, "Gravel", 0.6).drops(Drop::Rare {
+		id: items::FLINT,
+		one_in: 10,
+		otherwise: Some(GRAVEL as i16),
+	}),
+	Block::new(GOLD_ORE, "Gold Ore", 3.0).needs(ToolKind::Pickaxe, 2),
+	Block::new(IRON_ORE, "Iron Ore", 3.0).needs(ToolKind::Pickaxe, 1),
+	Block::rock(COAL_ORE, "Coal Ore", 3.0).drops_item(items::COAL),
+	Block::new(LOG, "Wood", 2.0),
+	Block::new(LEAVES, "Leaves", 0.2)
+		.opacity(1)
+		.drops(Drop::Rare {
+			id: SAPLING as i16,
+			one_in: 20,
+			otherwise: None,
+		}),
+	Block::new(SPONGE, "Sponge", 0.6),
+	Block::new(GLASS, "Glass", 0.3)
+		.transparent()
+		.drops(Drop::Nothing),
+	Block::new(CLOTH, "Cloth", 0.8),
+	Block::new(YELLOW_FLOWER, "Flower", 0.0).passable(),
+	Block::new(RED_ROSE, "Rose", 0.0).passable(),
+	Block::new(BROWN_MUSHROOM, "Mushroom", 0.0)
+		.passable()
+		.light(1),
+	Block::new(RED_MUSHROOM, "Mushroom", 0.0).passable(),
+	Block::new(GOLD_BLOCK, "Block of Gold", 3.0).needs(ToolKind::Pickaxe, 2),
+	Block::new(IRON_BLOCK, "Block of Iron", 5.0).needs(ToolKind::Pickaxe, 1),
+	Block::rock(DOUBLE_SLAB, "Double Stone Slab", 2.0).drops(Drop::Item {
+		id: SLAB as i16,
+		min: 2,
+		max: 2,
+	}),
+	Block::rock(SLAB, "Stone Slab", 2.0),
+	Block::rock(BRICKS, "Bricks", 2.0),
+	Block::new(TNT, "TNT", 0.0),
+	Block::new(BOOKSHELF, "Bookshelf", 1.5).drops(Drop::Nothing),
+	Block::rock(MOSSY_COBBLESTONE, "Moss Stone", 2.0),
+	Block::new(OBSIDIAN, "Obsidian", 10.0).needs(ToolKind::Pickaxe, 3),
+	Block::new(TORCH, "Torch", 0.0).passable().light(14),
+	Block::new(FIRE, "Fire", 0.0)
+		.passable()
+		.light(15)
+		.drops(Drop::Nothing),
+	Block::new(MOB_SPAWNER, "Monster Spawner", 5.0)
+		.transparent()
+		.drops(Drop::Nothing),
+	Block::new(WOODEN_STAIRS, "Wooden Stairs", 2.0).drops_item(PLANKS as i16),
+	Block::new(CHEST, "Chest", 2.5),
+	Block::new(REDSTONE_WIRE, "Redstone Dust", 0.0)
+		.passable()
+		.drops_item(items::REDSTONE),
+	Block::new(DIAMOND_ORE, "Diamond Ore", 3.0)
+		.needs(ToolKind::Pickaxe, 2)
+		.drops_item(items::DIAMOND),
+	Block::new(DIAMOND_BLOCK, "Block of Diamond", 5.0)
+		.needs(ToolKind::Pickaxe, 2),
+	Block::new(WORKBENCH, "Workbench", 2.5),
+	Block::new(CROPS, "Crops", 0.0)
+		.passable()
+		.drops_item(items::SEEDS),
+	Block::new(FARMLAND, "Farmland", 0.6).drops_item(DIRT as i16),
+	Block::rock(FURNACE, "Furnace", 3.5),
+	Block::rock(LIT_FURNACE, "Furnace", 3.5)
+		.light(13)
+		.drops_item(FURNACE as i16),
+	Block::new(SIGN_POST, "Sign", 1.0)
+		.passable()
+		.drops_item(items::SIGN),
+	Block::new(WOODEN_DOOR, "Wooden Door", 3.0)
+		.transparent()
+		.drops_item(items::WOODEN_DOOR),
+	Block::new(LADDER, "Ladder", 0.4).transparent(),
+	Block::new(RAILS, "Rails", 0.7).passable(),
+	Block::rock(COBBLESTONE_STAIRS, "Cobblestone Stairs", 2.0)
+		.drops_item(COBBLESTONE as i16),
+	Block::new(WALL_SIGN, "Sign", 1.0)
+		.passable()
+		.drops_item(items::SIGN),
+	Block::new(LEVER, "Lever", 0.5).passable(),
+	Block::rock(STONE_PRESSURE_PLATE, "Pressure Plate", 0.5).passable(),
+	Block::rock(IRON_DOOR, "Iron Door", 5.0)
+		.transparent()
+		.drops_item(items::IRON_DOOR),
+	Block::new(WOODEN_PRESSURE_PLATE, "Pressure Plate", 0.5).passable(),
+	Block::new(REDSTONE_ORE, "Redstone Ore", 3.0)
+		.needs(ToolKind::Pickaxe, 2)
+		.drops(Drop::Item {
+			id: items::REDSTONE,
+			min: 4,
+			max: 5,
+		}),
+	Block::new(LIT_REDSTONE_ORE, "Redstone Ore", 3.0)
+		.needs(ToolKind::Pickaxe, 2)
+		.light(9)
+		.drops(Drop::Item {
+			id: items::REDSTONE,
+			min: 4,
+			max: 5,
+		}),
+	Block::new(UNLIT_REDSTONE_TORCH, "Redstone Torch", 0.0)
+		.passable()
+		.drops_item(REDSTONE_TORCH as i16),
+	Block::new(REDSTONE_TORCH, "Redstone Torch", 0.0)
+		.passable()
+		.light(7),
+	Block::new(STONE_BUTTON, "Button", 0.5).passable(),
+	Block::new(SNOW_LAYER, "Snow", 0.1)
+		.passable()
+		.needs(ToolKind::Shovel, 0)
+		.drops_item(items::SNOWBALL),
+	Block::new(ICE, "Ice", 0.5).opacity(3).drops(Drop::Nothing),
+	Block::new(SNOW, "Snow", 0.2)
+		.needs(ToolKind::Shovel, 0)
+		.drops(Drop::Item {
+			id: items::SNOWBALL,
+			min: 4,
+			max: 4,
+		}),
+	Block::new(CACTUS, "Cactus", 0.4).transparent(),
+	Block::new(CLAY, "Clay", 0.6).drops(Drop::Item {
+		id: items::CLAY_BALL,
+		min: 4,
+		max: 4,
+	}),
+	Block::new(REED, "Reed", 0.0)
+		.passable()
+		.drops_item(items::REEDS),
+	Block::new(JUKEBOX, "Jukebox", 2.0),
+	Block::new(FENCE, "Fence", 2.0).transparent(),
+	Block::new(PUMPKIN, "Pumpkin", 1.0),
+	Block::rock(NETHERRACK, "Netherrack", 0.4),
+	Block::new(SOUL_SAND, "Soul Sand", 0.5),
+	Block::new(GLOWSTONE, "Glowstone", 0.3)
+		.light(15)
+		.drops(Drop::Item {
+			id: items::GLOWSTONE_DUST,
+			min: 2,
+			max: 4,
+		}),
+	Block::new(PORTAL, "Portal", -1.0)
+		.passable()
+		.light(11)
+		.drops(Drop::Nothing),
+	Block::new(JACK_O_LANTERN, "Jack 'o' Lantern", 1.0).light(15),
+];
+
+/// Looks up a block by id.
+pub fn get(id: u8) -> Option<&'static Block> {
+	BLOCKS
+		.binary_search_by_key(&id, |block| block.id)
+		.ok()
+		.map(|index| &BLOCKS[index])
+}
+
+/// Whether entities collide with the block. Unknown blocks are treated as
+/// solid.
+pub fn has_collision(block: u8) -> bool {
+	get(block).is_none_or(|block| block.collides)
+}
+
+/// Whether placing a block may overwrite this one.
+pub fn is_replaceable(block: u8) -> bool {
+	get(block).is_some_and(|block| block.replaceable)
+}
@@ -0,0 +1,71 @@
+use alloc::vec::Vec;
+use irox_bits::{Bits, BitsErrorKind, MutBits};
+
+/// Reads from a borrowed byte slice.
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+	data: &'a [u8],
+	position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+	pub fn new(data: &'a [u8]) -> Self {
+		Self { data, position: 0 }
+	}
+
+	/// Returns the bytes that have not been read yet.
+	pub fn remaining(&self) -> &'a [u8] {
+		&self.data[self.position..]
+	}
+}
+
+impl Bits for ByteReader<'_> {
+	fn next_u8(&mut self) -> Result<Option<u8>, irox_bits::Error> {
+		let Some(byte) = self.data.get(self.position) else {
+			return Err(irox_bits::Error::new(
+				BitsErrorKind::UnexpectedEof,
+				"Unexpected end of buffer",
+			));
+		};
+		self.position += 1;
+
+		Ok(Some(*byte))
+	}
+}
+
+/// Writes into a growable byte buffer.
+#[derive(Debug, Clone, Default)]
+pub struct ByteWriter(Vec<u8>);
+
+impl ByteWriter {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.0
+	}
+
+	pub fn into_inner(self) -> Vec<u8> {
+		self.0
+	}
+
+	/// Empties the buffer, keeping its allocation for reuse.
+	pub fn clear(&mut self) {
+		self.0.clear();
+	}
+}
+
+impl MutBits for ByteWriter {
+	fn write_u8(&mut self, val: u8) -> Result<(), irox_bits::Error> {
+		self.0.push(val);
+
+		Ok(())
+	}
+
+	fn write_all_bytes(&mut self, val: &[u8]) -> Result<(), irox_bits::Error> {
+		self.0.extend_from_slice(val);
+
+		Ok(())
+	}
+}
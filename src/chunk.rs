@@ -0,0 +1,264 @@
+use crate::{
+	nbt::{Compound, Tag},
+	packets::ClientboundPacket,
+	InvalidLengthSnafu, MissingFieldSnafu, Result,
+};
+use alloc::{boxed::Box, vec, vec::Vec};
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use snafu::OptionExt;
+
+pub const CHUNK_WIDTH: usize = 16;
+pub const CHUNK_HEIGHT: usize = 128;
+pub const CHUNK_VOLUME: usize = CHUNK_WIDTH * CHUNK_HEIGHT * CHUNK_WIDTH;
+
+/// Returns the index of a block within a chunk's arrays. Alpha stores
+/// columns contiguously, so `y` varies fastest, then `z`, then `x`.
+pub const fn block_index(x: usize, y: usize, z: usize) -> usize {
+	y + z * CHUNK_HEIGHT + x * CHUNK_HEIGHT * CHUNK_WIDTH
+}
+
+fn boxed_array<const N: usize>(value: u8) -> Box<[u8; N]> {
+	vec![value; N].into_boxed_slice().try_into().unwrap()
+}
+
+/// Copies `bytes` into an array, failing with `InvalidLength` unless it has
+/// exactly `N` elements.
+fn exact_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N]> {
+	bytes.try_into().ok().context(InvalidLengthSnafu {
+		length: bytes.len() as i64,
+	})
+}
+
+/// Four bits per block, two blocks to a byte. The block with the even index
+/// is stored in the low nibble.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NibbleArray(Box<[u8; CHUNK_VOLUME / 2]>);
+
+impl Default for NibbleArray {
+	fn default() -> Self {
+		Self::filled(0)
+	}
+}
+
+impl NibbleArray {
+	/// Creates an array with every nibble set to `value`.
+	pub fn filled(value: u8) -> Self {
+		let value = value & 0x0F;
+		Self(boxed_array(value << 4 | value))
+	}
+
+	pub fn get(&self, index: usize) -> u8 {
+		let byte = self.0[index >> 1];
+		if index & 1 == 0 {
+			byte & 0x0F
+		} else {
+			byte >> 4
+		}
+	}
+
+	pub fn set(&mut self, index: usize, value: u8) {
+		let byte = &mut self.0[index >> 1];
+		if index & 1 == 0 {
+			*byte = (*byte & 0xF0) | (value & 0x0F);
+		} else {
+			*byte = (*byte & 0x0F) | (value << 4);
+		}
+	}
+
+	/// Wraps an already packed array, failing if it has the wrong length.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+		Ok(Self(Box::new(exact_array(bytes)?)))
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		self.0.as_slice()
+	}
+}
+
+/// A 16x128x16 column of blocks. Local coordinates passed to the accessors
+/// must be within `0..16` horizontally and `0..128` vertically.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+	pub x: i32,
+	pub z: i32,
+	pub blocks: Box<[u8; CHUNK_VOLUME]>,
+	pub data: NibbleArray,
+	pub sky_light: NibbleArray,
+	pub block_light: NibbleArray,
+	/// Whether ores, trees and other features have been placed yet. Terrain
+	/// is populated once the neighbouring chunks exist.
+	pub populated: bool,
+	/// Entity and tile entity compounds loaded from disk. They are kept
+	/// as-is so saving a chunk does not lose them.
+	pub entities: Vec<Tag>,
+	pub tile_entities: Vec<Tag>,
+}
+
+impl Default for Chunk {
+	fn default() -> Self {
+		Self::new(0, 0)
+	}
+}
+
+impl Chunk {
+	/// Creates a chunk filled with air and no light.
+	pub fn new(x: i32, z: i32) -> Self {
+		Self {
+			x,
+			z,
+			blocks: boxed_array(0),
+			data: NibbleArray::default(),
+			sky_light: NibbleArray::default(),
+			block_light: NibbleArray::default(),
+			populated: false,
+			entities: Vec::new(),
+			tile_entities: Vec::new(),
+		}
+	}
+
+	pub fn get_block(&self, x: usize, y: usize, z: usize) -> u8 {
+		self.blocks[block_index(x, y, z)]
+	}
+
+	pub fn set_block(&mut self, x: usize, y: usize, z: usize, id: u8) {
+		self.blocks[block_index(x, y, z)] = id;
+	}
+
+	pub fn get_meta(&self, x: usize, y: usize, z: usize) -> u8 {
+		self.data.get(block_index(x, y, z))
+	}
+
+	pub fn set_meta(&mut self, x: usize, y: usize, z: usize, meta: u8) {
+		self.data.set(block_index(x, y, z), meta);
+	}
+
+	pub fn get_sky_light(&self, x: usize, y: usize, z: usize) -> u8 {
+		self.sky_light.get(block_index(x, y, z))
+	}
+
+	pub fn set_sky_light(&mut self, x: usize, y: usize, z: usize, light: u8) {
+		self.sky_light.set(block_index(x, y, z), light);
+	}
+
+	pub fn get_block_light(&self, x: usize, y: usize, z: usize) -> u8 {
+		self.block_light.get(block_index(x, y, z))
+	}
+
+	pub fn set_block_light(&mut self, x: usize, y: usize, z: usize, light: u8) {
+		self.block_light.set(block_index(x, y, z), light);
+	}
+
+	/// Returns the brightness of a block, whichever of sky and block light
+	/// is stronger.
+	pub fn get_light(&self, x: usize, y: usize, z: usize) -> u8 {
+		self.get_sky_light(x, y, z)
+			.max(self.get_block_light(x, y, z))
+	}
+
+	/// Builds the MAP_CHUNK packet carrying the whole 16x128x16 column.
+	pub fn to_packet(&self) -> ClientboundPacket {
+		let mut to_compress = self.blocks.to_vec();
+		to_compress.extend_from_slice(self.data.as_bytes());
+		to_compress.extend_from_slice(self.block_light.as_bytes());
+		to_compress.extend_from_slice(self.sky_light.as_bytes());
+
+		ClientboundPacket::MapChunk {
+			x: self.x * 16,
+			y: 0,
+			z: self.z * 16,
+			size_x: (CHUNK_WIDTH - 1) as u8,
+			size_y: (CHUNK_HEIGHT - 1) as u8,
+			size_z: (CHUNK_WIDTH - 1) as u8,
+			compressed_data: compress_to_vec_zlib(&to_compress, 6),
+		}
+	}
+
+	/// Returns the height of the highest non-air block in each column plus
+	/// one, indexed by `z << 4 | x`.
+	pub fn height_map(&self) -> [u8; CHUNK_WIDTH * CHUNK_WIDTH] {
+		let mut height_map = [0u8; CHUNK_WIDTH * CHUNK_WIDTH];
+
+		for x in 0..CHUNK_WIDTH {
+			for z in 0..CHUNK_WIDTH {
+				let column = block_index(x, 0, z);
+				let height = self.blocks[column..column + CHUNK_HEIGHT]
+					.iter()
+					.rposition(|block| *block != 0)
+					.map_or(0, |y| y + 1);
+				height_map[z << 4 | x] = height as u8;
+			}
+		}
+
+		height_map
+	}
+
+	/// Converts the chunk to the root compound of an Alpha chunk file.
+	pub fn to_nbt(&self, last_update: i64) -> Tag {
+		let level = Compound::new()
+			.with("xPos", Tag::Int(self.x))
+			.with("zPos", Tag::Int(self.z))
+			.with("LastUpdate", Tag::Long(last_update))
+			.with("TerrainPopulated", Tag::Byte(self.populated as i8))
+			.with("Blocks", Tag::ByteArray(self.blocks.to_vec()))
+			.with("Data", Tag::ByteArray(self.data.as_bytes().to_vec()))
+			.with(
+				"SkyLight",
+				Tag::ByteArray(self.sky_light.as_bytes().to_vec()),
+			)
+			.with(
+				"BlockLight",
+				Tag::ByteArray(self.block_light.as_bytes().to_vec()),
+			)
+			.with("HeightMap", Tag::ByteArray(self.height_map().to_vec()))
+			.with("Entities", Tag::list(self.entities.clone()))
+			.with("TileEntities", Tag::list(self.tile_entities.clone()));
+
+		Tag::Compound(Compound::new().with("Level", Tag::Compound(level)))
+	}
+
+	/// Reads a chunk from the root compound of an Alpha chunk file.
+	pub fn from_nbt(tag: &Tag) -> Result<Self> {
+		let level = tag
+			.as_compound()
+			.and_then(|root| root.get("Level"))
+			.and_then(Tag::as_compound)
+			.context(MissingFieldSnafu { name: "Level" })?;
+
+		let int = |name: &'static str| {
+			level
+				.get(name)
+				.and_then(Tag::as_int)
+				.context(MissingFieldSnafu { name })
+		};
+		let bytes = |name: &'static str| {
+			level
+				.get(name)
+				.and_then(Tag::as_byte_array)
+				.context(MissingFieldSnafu { name })
+		};
+		let nibbles =
+			|name: &'static str| NibbleArray::from_bytes(bytes(name)?);
+		let list = |name: &'static str| {
+			level
+				.get(name)
+				.and_then(Tag::as_list)
+				.map(<[Tag]>::to_vec)
+				.unwrap_or_default()
+		};
+
+		Ok(Self {
+			x: int("xPos")?,
+			z: int("zPos")?,
+			blocks: Box::new(exact_array(bytes("Blocks")?)?),
+			data: nibbles("Data")?,
+			sky_light: nibbles("SkyLight")?,
+			block_light: nibbles("BlockLight")?,
+			populated: level
+				.get("TerrainPopulated")
+				.and_then(Tag::as_byte)
+				.is_some_and(|populated| populated != 0),
+			entities: list("Entities"),
+			tile_entities: list("TileEntities"),
+		})
+	}
+}
@@ -0,0 +1,775 @@
+//! Commands typed into chat with a leading slash, or into the console.
+
+use crate::server::Server;
+use snafu::{ensure, OptionExt, Snafu};
+use std::{collections::BTreeMap, net::IpAddr, str::FromStr};
+
+/// Why a command did not run. The message is sent back to whoever issued
+/// it.
+#[derive(Debug, Snafu)]
+pub enum CommandError {
+	#[snafu(display("Unknown command. Type /help for a list of commands."))]
+	UnknownCommand,
+	#[snafu(display("You do not have permission to use this command."))]
+	NoPermission,
+	#[snafu(display("Usage: /{}", format!("{name} {usage}").trim_end()))]
+	Usage {
+		name: &'static str,
+		usage: &'static str,
+	},
+	#[snafu(display("Invalid {argument}: {value:?}"))]
+	InvalidArgument {
+		argument: &'static str,
+		value: String,
+	},
+	#[snafu(display("{message}"))]
+	Failed { message: String },
+}
+
+/// Who issued a command, and where replies to it go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sender {
+	/// A logged in player, by session id.
+	Player(i32),
+	/// The server's standard input. It may run every command.
+	Console,
+}
+
+/// Who may run a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+	Everyone,
+	/// Only the console and players listed in `ops.txt`.
+	Operator,
+}
+
+/// Runs a command for whoever issued it.
+pub type Handler =
+	fn(&mut Server, Sender, Arguments) -> Result<(), CommandError>;
+
+#[derive(Clone, Copy)]
+pub struct Command {
+	pub name: &'static str,
+	/// The arguments, as shown after the name in help and usage replies.
+	pub usage: &'static str,
+	pub description: &'static str,
+	pub permission: Permission,
+	pub run: Handler,
+}
+
+/// The commands a server understands, by name.
+#[derive(Default)]
+pub struct Commands {
+	commands: BTreeMap<&'static str, Command>,
+}
+
+impl Commands {
+	/// Creates the registry with the commands every server has.
+	pub fn with_builtins() -> Self {
+		let mut commands = Self::default();
+		commands.register(Command {
+			name: "help",
+			usage: "",
+			description: "Lists the commands you can use",
+			permission: Permission::Everyone,
+			run: help,
+		});
+		commands.register(Command {
+			name: "list",
+			usage: "",
+			description: "Lists the connected players",
+			permission: Permission::Everyone,
+			run: list,
+		});
+		commands.register(Command {
+			name: "me",
+			usage: "<action>",
+			description: "Describes what you are doing",
+			permission: Permission::Everyone,
+			run: me,
+		});
+		commands.register(Command {
+			name: "tell",
+			usage: "<player> <message>",
+			description: "Sends a private message",
+			permission: Permission::Everyone,
+			run: tell,
+		});
+		commands.register(Command {
+			name: "say",
+			usage: "<message>",
+			description: "Broadcasts a message to everyone",
+			permission: Permission::Operator,
+			run: say,
+		});
+		commands.register(Command {
+			name: "kick",
+			usage: "<player> [reason]",
+			description: "Disconnects a player",
+			permission: Permission::Operator,
+			run: kick,
+		});
+		commands.register(Command {
+			name: "op",
+			usage: "<player>",
+			description: "Makes a player an operator",
+			permission: Permission::Operator,
+			run: op,
+		});
+		commands.register(Command {
+			name: "deop",
+			usage: "<player>",
+			description: "Takes operator status away from a player",
+			permission: Permission::Operator,
+			run: deop,
+		});
+		commands.register(Command {
+			name: "ban",
+			usage: "<player>",
+			description: "Stops a player from logging in",
+			permission: Permission::Operator,
+			run: ban,
+		});
+		commands.register(Command {
+			name: "pardon",
+			usage: "<player>",
+			description: "Lets a banned player log in again",
+			permission: Permission::Operator,
+			run: pardon,
+		});
+		commands.register(Command {
+			name: "ban-ip",
+			usage: "<address|player>",
+			description: "Stops everyone connecting from an address",
+			permission: Permission::Operator,
+			run: ban_ip,
+		});
+		commands.register(Command {
+			name: "pardon-ip",
+			usage: "<address>",
+			description: "Lets a banned address connect again",
+			permission: Permission::Operator,
+			run: pardon_ip,
+		});
+		commands.register(Command {
+			name: "whitelist",
+			usage: "<add|remove|list> [player]",
+			description: "Edits who may log in while the whitelist is on",
+			permission: Permission::Operator,
+			run: whitelist,
+		});
+		commands.register(Command {
+			name: "tp",
+			usage: "<player> <target>",
+			description: "Teleports a player to another player",
+			permission: Permission::Operator,
+			run: tp,
+		});
+		commands.register(Command {
+			name: "time",
+			usage: "<set|add> <ticks>",
+			description: "Changes the time of day",
+			permission: Permission::Operator,
+			run: time,
+		});
+		commands.register(Command {
+			name: "save-all",
+			usage: "",
+			description: "Saves the world to disk",
+			permission: Permission::Operator,
+			run: save_all,
+		});
+		commands.register(Command {
+			name: "stop",
+			usage: "",
+			description: "Saves the world and shuts the server down",
+			permission: Permission::Operator,
+			run: stop,
+		});
+
+		commands
+	}
+
+	/// Adds a command, replacing any other command with the same name.
+	pub fn register(&mut self, command: Command) {
+		self.commands.insert(command.name, command);
+	}
+
+	/// Looks a command up by name, ignoring case.
+	pub fn get(&self, name: &str) -> Option<&Command> {
+		self.commands.get(name.to_ascii_lowercase().as_str())
+	}
+
+	/// Looks up the command a sender asked for, failing with the reply they
+	/// get if there is no such command or it is for operators and
+	/// `operator` says they are not one.
+	pub fn find(
+		&self,
+		name: &str,
+		operator: bool,
+	) -> Result<Command, CommandError> {
+		let command = self.get(name).copied().context(UnknownCommandSnafu)?;
+		ensure!(
+			operator || command.permission == Permission::Everyone,
+			NoPermissionSnafu
+		);
+
+		Ok(command)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &Command> {
+		self.commands.values()
+	}
+}
+
+/// The text following a command's name, consumed one argument at a time.
+pub struct Arguments<'a> {
+	name: &'static str,
+	usage: &'static str,
+	rest: &'a str,
+}
+
+impl<'a> Arguments<'a> {
+	pub fn new(command: &Command, input: &'a str) -> Self {
+		Self {
+			name: command.name,
+			usage: command.usage,
+			rest: input.trim(),
+		}
+	}
+
+	/// Takes the next whitespace separated argument and parses it.
+	/// `argument` names it in the reply if it does not parse.
+	pub fn next<T: FromStr>(
+		&mut self,
+		argument: &'static str,
+	) -> Result<T, CommandError> {
+		if self.rest.is_empty() {
+			return self.usage();
+		}
+		let (value, rest) = self
+			.rest
+			.split_once(char::is_whitespace)
+			.unwrap_or((self.rest, ""));
+		self.rest = rest.trim_start();
+
+		value.parse().map_err(|_| CommandError::InvalidArgument {
+			argument,
+			value: value.to_string(),
+		})
+	}
+
+	/// Takes everything that is left, which must not be empty.
+	pub fn rest(&mut self) -> Result<&'a str, CommandError> {
+		if self.rest.is_empty() {
+			return self.usage();
+		}
+
+		Ok(core::mem::take(&mut self.rest))
+	}
+
+	/// Fails unless every argument has been consumed.
+	pub fn finish(self) -> Result<(), CommandError> {
+		if !self.rest.is_empty() {
+			return self.usage();
+		}
+
+		Ok(())
+	}
+
+	fn usage<T>(&self) -> Result<T, CommandError> {
+		UsageSnafu {
+			name: self.name,
+			usage: self.usage,
+		}
+		.fail()
+	}
+}
+
+fn help(
+	server: &mut Server,
+	sender: Sender,
+	arguments: Arguments,
+) -> Result<(), CommandError> {
+	arguments.finish()?;
+
+	let lines: Vec<_> = server
+		.commands()
+		.iter()
+		.filter(|command| server.has_permission(sender, command.permission))
+		.map(|command| {
+			let name = [command.name, command.usage].join(" ");
+			format!("/{} - {}", name.trim_end(), command.description)
+		})
+		.collect();
+	for line in lines {
+		server.reply(sender, line);
+	}
+
+	Ok(())
+}
+
+fn list(
+	server: &mut Server,
+	sender: Sender,
+	arguments: Arguments,
+) -> Result<(), CommandError> {
+	arguments.finish()?;
+
+	let names: Vec<_> = server
+		.players()
+		.map(|(_, player)| player.username.as_str())
+		.collect();
+	let message = format!("Connected players: {}", names.join(", "));
+	server.reply(sender, message);
+
+	Ok(())
+}
+
+fn me(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let action = arguments.rest()?;
+
+	let message = format!("* {} {action}", server.sender_name(sender));
+	server.broadcast_message(&message);
+
+	Ok(())
+}
+
+fn tell(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let target: String = arguments.next("player")?;
+	let message = arguments.rest()?;
+
+	let target_id = online_player(server, &target)?;
+	let name = server.sender_name(sender).to_string();
+	let target = server.username(target_id).to_string();
+	server.send_message(target_id, format!("{name} whispers {message}"));
+	server.reply(sender, format!("You whisper to {target}: {message}"));
+
+	Ok(())
+}
+
+fn say(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let message = arguments.rest()?;
+
+	let message = format!("[{}] {message}", server.sender_name(sender));
+	tracing::info!("{message}");
+	server.broadcast_message(&message);
+
+	Ok(())
+}
+
+fn kick(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let name: String = arguments.next("player")?;
+	let reason = arguments.rest().unwrap_or("Kicked by an operator");
+
+	let target = online_player(server, &name)?;
+	server.kick(target, reason);
+	server.reply(sender, format!("Kicked {name}"));
+
+	Ok(())
+}
+
+fn op(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let name: String = arguments.next("player")?;
+	arguments.finish()?;
+
+	if !saved(server.lists_mut().ops.add(&name))? {
+		return FailedSnafu {
+			message: format!("{name} is already an operator"),
+		}
+		.fail();
+	}
+	tracing::info!("{} opped {name}", server.sender_name(sender));
+	server.reply(sender, format!("Opped {name}"));
+	if let Some(target) = server.find_player(&name) {
+		server.send_message(target, "You are now an operator");
+	}
+
+	Ok(())
+}
+
+fn deop(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let name: String = arguments.next("player")?;
+	arguments.finish()?;
+
+	if !saved(server.lists_mut().ops.remove(&name))? {
+		return FailedSnafu {
+			message: format!("{name} is not an operator"),
+		}
+		.fail();
+	}
+	tracing::info!("{} de-opped {name}", server.sender_name(sender));
+	server.reply(sender, format!("De-opped {name}"));
+	if let Some(target) = server.find_player(&name) {
+		server.send_message(target, "You are no longer an operator");
+	}
+
+	Ok(())
+}
+
+fn ban(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let name: String = arguments.next("player")?;
+	arguments.finish()?;
+
+	if !saved(server.lists_mut().banned_players.add(&name))? {
+		return FailedSnafu {
+			message: format!("{name} is already banned"),
+		}
+		.fail();
+	}
+	tracing::info!("{} banned {name}", server.sender_name(sender));
+	if let Some(target) = server.find_player(&name) {
+		server.kick(target, "Banned by an operator");
+	}
+	server.reply(sender, format!("Banned {name}"));
+
+	Ok(())
+}
+
+fn pardon(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let name: String = arguments.next("player")?;
+	arguments.finish()?;
+
+	if !saved(server.lists_mut().banned_players.remove(&name))? {
+		return FailedSnafu {
+			message: format!("{name} is not banned"),
+		}
+		.fail();
+	}
+	tracing::info!("{} pardoned {name}", server.sender_name(sender));
+	server.reply(sender, format!("Pardoned {name}"));
+
+	Ok(())
+}
+
+fn ban_ip(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let target: String = arguments.next("address")?;
+	arguments.finish()?;
+
+	// A player's name stands for the address they are connected from.
+	let address = match target.parse::<IpAddr>() {
+		Ok(address) => address.to_canonical(),
+		Err(_) => {
+			let player = server.find_player(&target).ok_or_else(|| {
+				CommandError::InvalidArgument {
+					argument: "address",
+					value: target.clone(),
+				}
+			})?;
+			server.address(player).expect("players have a session")
+		}
+	};
+
+	if !saved(server.lists_mut().banned_ips.add(&address.to_string()))? {
+		return FailedSnafu {
+			message: format!("{address} is already banned"),
+		}
+		.fail();
+	}
+	tracing::info!(
+		"{} banned the address {address}",
+		server.sender_name(sender)
+	);
+	let sessions: Vec<_> = server.sessions_from(address).collect();
+	for session in sessions {
+		server.kick(session, "Banned by an operator");
+	}
+	server.reply(sender, format!("Banned the address {address}"));
+
+	Ok(())
+}
+
+fn pardon_ip(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let address: IpAddr = arguments.next("address")?;
+	arguments.finish()?;
+
+	let address = address.to_canonical();
+	if !saved(server.lists_mut().banned_ips.remove(&address.to_string()))? {
+		return FailedSnafu {
+			message: format!("{address} is not banned"),
+		}
+		.fail();
+	}
+	tracing::info!(
+		"{} pardoned the address {address}",
+		server.sender_name(sender)
+	);
+	server.reply(sender, format!("Pardoned the address {address}"));
+
+	Ok(())
+}
+
+fn whitelist(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let action: String = arguments.next("action")?;
+
+	match action.to_ascii_lowercase().as_str() {
+		"add" => {
+			let name: String = arguments.next("player")?;
+			arguments.finish()?;
+			if !saved(server.lists_mut().whitelist.add(&name))? {
+				return FailedSnafu {
+					message: format!("{name} is already white-listed"),
+				}
+				.fail();
+			}
+			tracing::info!(
+				"{} white-listed {name}",
+				server.sender_name(sender)
+			);
+			server.reply(sender, format!("Added {name} to the whitelist"));
+		}
+		"remove" => {
+			let name: String = arguments.next("player")?;
+			arguments.finish()?;
+			if !saved(server.lists_mut().whitelist.remove(&name))? {
+				return FailedSnafu {
+					message: format!("{name} is not white-listed"),
+				}
+				.fail();
+			}
+			tracing::info!(
+				"{} removed {name} from the whitelist",
+				server.sender_name(sender)
+			);
+			server.reply(sender, format!("Removed {name} from the whitelist"));
+		}
+		"list" => {
+			arguments.finish()?;
+			let names: Vec<_> = server.lists().whitelist.iter().collect();
+			let message = format!("White-listed players: {}", names.join(", "));
+			server.reply(sender, message);
+		}
+		_ => {
+			return InvalidArgumentSnafu {
+				argument: "action",
+				value: action,
+			}
+			.fail()
+		}
+	}
+
+	Ok(())
+}
+
+fn tp(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let name: String = arguments.next("player")?;
+	let target: String = arguments.next("target")?;
+	arguments.finish()?;
+
+	let id = online_player(server, &name)?;
+	let target_id = online_player(server, &target)?;
+	let destination = server
+		.player(target_id)
+		.map(|player| (player.x, player.y, player.z))
+		.expect("online players are logged in");
+	server.teleport(id, destination);
+	let name = server.username(id).to_string();
+	let target = server.username(target_id).to_string();
+	server.reply(sender, format!("Teleported {name} to {target}"));
+
+	Ok(())
+}
+
+fn time(
+	server: &mut Server,
+	sender: Sender,
+	mut arguments: Arguments,
+) -> Result<(), CommandError> {
+	let action: String = arguments.next("action")?;
+	let ticks: i64 = arguments.next("ticks")?;
+	arguments.finish()?;
+
+	let time = match action.to_ascii_lowercase().as_str() {
+		"set" => ticks,
+		"add" => server.time().saturating_add(ticks),
+		_ => {
+			return InvalidArgumentSnafu {
+				argument: "action",
+				value: action,
+			}
+			.fail()
+		}
+	};
+	server.set_time(time);
+	server.reply(sender, format!("Set the time to {time}"));
+
+	Ok(())
+}
+
+fn save_all(
+	server: &mut Server,
+	sender: Sender,
+	arguments: Arguments,
+) -> Result<(), CommandError> {
+	arguments.finish()?;
+
+	server.reply(sender, "Saving...");
+	server.save().map_err(|err| {
+		tracing::error!("Failed to save the world: {err}");
+		CommandError::Failed {
+			message: "Failed to save the world".into(),
+		}
+	})?;
+	server.reply(sender, "Saved the world");
+
+	Ok(())
+}
+
+fn stop(
+	server: &mut Server,
+	sender: Sender,
+	arguments: Arguments,
+) -> Result<(), CommandError> {
+	arguments.finish()?;
+
+	tracing::info!("{} stopped the server", server.sender_name(sender));
+	server.stop();
+
+	Ok(())
+}
+
+/// Finds the session of a logged in player, failing with a reply if there
+/// is none.
+fn online_player(server: &Server, name: &str) -> Result<i32, CommandError> {
+	server
+		.find_player(name)
+		.ok_or_else(|| CommandError::Failed {
+			message: format!("There's no player called {name}"),
+		})
+}
+
+/// Logs a failure to write a list file and reports it to the sender
+/// without the details.
+fn saved<T>(result: crate::Result<T>) -> Result<T, CommandError> {
+	result.map_err(|err| {
+		tracing::error!("Failed to save a player list: {err}");
+		CommandError::Failed {
+			message: "Failed to save the change".into(),
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn arguments_for<'a>(name: &str, input: &'a str) -> Arguments<'a> {
+		let commands = Commands::with_builtins();
+		Arguments::new(commands.get(name).unwrap(), input)
+	}
+
+	fn is_usage<T>(result: Result<T, CommandError>, expected: &str) -> bool {
+		matches!(result, Err(err @ CommandError::Usage { .. })
+			if err.to_string() == expected)
+	}
+
+	#[test]
+	fn splits_arguments_on_whitespace() {
+		let mut arguments = arguments_for("tell", "  Steve \t hello   there ");
+		assert_eq!(arguments.next::<String>("player").unwrap(), "Steve");
+		assert_eq!(arguments.rest().unwrap(), "hello   there");
+		arguments.finish().unwrap();
+
+		let mut arguments = arguments_for("time", "add 600");
+		assert_eq!(arguments.next::<String>("action").unwrap(), "add");
+		assert_eq!(arguments.next::<i64>("ticks").unwrap(), 600);
+		arguments.finish().unwrap();
+	}
+
+	#[test]
+	fn replies_with_usage_for_missing_or_extra_arguments() {
+		let usage = "Usage: /tell <player> <message>";
+		assert!(is_usage(
+			arguments_for("tell", "").next::<String>("player"),
+			usage
+		));
+		let mut tell = arguments_for("tell", "Steve");
+		tell.next::<String>("player").unwrap();
+		assert!(is_usage(tell.rest(), usage));
+
+		assert!(is_usage(
+			arguments_for("list", "extra").finish(),
+			"Usage: /list"
+		));
+		let mut op = arguments_for("op", "Steve Alex");
+		op.next::<String>("player").unwrap();
+		assert!(is_usage(op.finish(), "Usage: /op <player>"));
+	}
+
+	#[test]
+	fn names_arguments_that_do_not_parse() {
+		let mut arguments = arguments_for("time", "set noon");
+		arguments.next::<String>("action").unwrap();
+		let err = arguments.next::<i64>("ticks").unwrap_err();
+		assert_eq!(err.to_string(), "Invalid ticks: \"noon\"");
+	}
+
+	#[test]
+	fn finds_commands_by_name_ignoring_case() {
+		let commands = Commands::with_builtins();
+		assert_eq!(commands.find("LIST", false).unwrap().name, "list");
+		assert!(matches!(
+			commands.find("fly", true),
+			Err(CommandError::UnknownCommand)
+		));
+	}
+
+	#[test]
+	fn keeps_operator_commands_from_everyone_else() {
+		let commands = Commands::with_builtins();
+		assert!(matches!(
+			commands.find("stop", false),
+			Err(CommandError::NoPermission)
+		));
+		assert_eq!(commands.find("stop", true).unwrap().name, "stop");
+		assert_eq!(commands.find("me", false).unwrap().name, "me");
+	}
+}
@@ -0,0 +1,338 @@
+use crate::{
+	auth::{SessionServer, DEFAULT_SESSION_SERVER},
+	InvalidPropertySnafu, PropertyOutOfRangeSnafu, Result,
+};
+use oxidized_alpha::generator::GeneratorSettings;
+use snafu::{ensure, OptionExt};
+use std::{
+	collections::HashMap,
+	fs,
+	io::ErrorKind,
+	net::{IpAddr, Ipv6Addr, SocketAddr},
+	ops::RangeInclusive,
+	path::Path,
+	str::FromStr,
+	time::Duration,
+};
+
+/// Options read from a `server.properties` file. Keys that are missing keep
+/// their defaults, and so does everything if the file does not exist.
+#[derive(Debug, Clone)]
+pub struct Config {
+	/// Where to listen for connections, from `server-ip` and `server-port`.
+	/// An empty `server-ip` listens on every interface.
+	pub address: SocketAddr,
+	/// Sent to clients as they log in.
+	pub motd: String,
+	/// How many players may be logged in at once.
+	pub max_players: u32,
+	/// The directory the world is stored in.
+	pub level_name: String,
+	/// The seed of a newly created world, or a random one if unset. Text
+	/// that is not a number is hashed the way Java hashes strings.
+	pub seed: Option<i64>,
+	pub generator: GeneratorSettings,
+	/// How many chunks around each player are sent to them, in every
+	/// direction.
+	pub view_distance: u32,
+	/// Whether players have to be verified by the session server.
+	pub online_mode: bool,
+	/// The `checkserver` endpoint online mode verifies players with.
+	pub session_server: SessionServer,
+	/// Whether only players listed in `white-list.txt` may log in.
+	pub white_list: bool,
+	/// How many blocks around the spawn point only operators may change.
+	/// Zero turns the protection off.
+	pub spawn_protection: u32,
+	/// How long a client may stay silent before it is disconnected.
+	pub timeout: Duration,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			address: SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 25565),
+			motd: "A Minecraft Server".into(),
+			max_players: 20,
+			level_name: "world".into(),
+			seed: None,
+			generator: GeneratorSettings::default(),
+			view_distance: 10,
+			online_mode: false,
+			session_server: DEFAULT_SESSION_SERVER
+				.parse()
+				.expect("the default session server is a valid URL"),
+			white_list: false,
+			spawn_protection: 16,
+			timeout: Duration::from_secs(60),
+		}
+	}
+}
+
+impl Config {
+	pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+		let properties = match fs::read_to_string(path) {
+			Ok(text) => parse_properties(&text),
+			Err(err) if err.kind() == ErrorKind::NotFound => HashMap::new(),
+			Err(err) => return Err(err.into()),
+		};
+		let get = |key: &str| {
+			properties
+				.get(key)
+				.map(String::as_str)
+				.filter(|value| !value.is_empty())
+		};
+		let defaults = Self::default();
+
+		let ip = match get("server-ip") {
+			Some(ip) => ip.parse().ok().context(InvalidPropertySnafu {
+				key: "server-ip",
+				value: ip,
+			})?,
+			None => defaults.address.ip(),
+		};
+		let port = parse_in_range(&properties, "server-port", 1..=u16::MAX)?
+			.unwrap_or(defaults.address.port());
+
+		Ok(Self {
+			address: SocketAddr::new(ip, port),
+			motd: get("motd").map_or(defaults.motd, str::to_string),
+			max_players: parse_in_range(&properties, "max-players", 1..=1000)?
+				.unwrap_or(defaults.max_players),
+			level_name: get("level-name")
+				.map_or(defaults.level_name, str::to_string),
+			seed: get("level-seed").map(|seed| {
+				seed.parse().unwrap_or_else(|_| java_hash(seed) as i64)
+			}),
+			generator: GeneratorSettings::parse(
+				get("level-type").unwrap_or("default"),
+				get("generator-settings").unwrap_or_default(),
+			)?,
+			view_distance: parse_in_range(
+				&properties,
+				"view-distance",
+				1..=15,
+			)?
+			.unwrap_or(defaults.view_distance),
+			online_mode: parse(&properties, "online-mode")?
+				.unwrap_or(defaults.online_mode),
+			session_server: parse(&properties, "session-server")?
+				.unwrap_or(defaults.session_server),
+			white_list: parse(&properties, "white-list")?
+				.unwrap_or(defaults.white_list),
+			spawn_protection: parse(&properties, "spawn-protection")?
+				.unwrap_or(defaults.spawn_protection),
+			timeout: parse_in_range(&properties, "timeout", 1..=3600u32)?
+				.map_or(defaults.timeout, |seconds| {
+					Duration::from_secs(seconds.into())
+				}),
+		})
+	}
+}
+
+/// Parses the value of `key`, if it is set to something.
+fn parse<T: FromStr>(
+	properties: &HashMap<String, String>,
+	key: &'static str,
+) -> Result<Option<T>> {
+	properties
+		.get(key)
+		.filter(|value| !value.is_empty())
+		.map(|value| {
+			value.parse().ok().context(InvalidPropertySnafu {
+				key,
+				value: value.clone(),
+			})
+		})
+		.transpose()
+}
+
+/// Parses the value of `key`, if it is set, and checks that it lies within
+/// `range`.
+fn parse_in_range<T>(
+	properties: &HashMap<String, String>,
+	key: &'static str,
+	range: RangeInclusive<T>,
+) -> Result<Option<T>>
+where
+	T: FromStr + PartialOrd + Copy + Into<i64>,
+{
+	let value = parse(properties, key)?;
+	if let Some(value) = value {
+		ensure!(
+			range.contains(&value),
+			PropertyOutOfRangeSnafu {
+				key,
+				value: value.into(),
+				min: (*range.start()).into(),
+				max: (*range.end()).into(),
+			}
+		);
+	}
+
+	Ok(value)
+}
+
+/// `String.hashCode`, which the Java server uses to turn a textual seed
+/// into a number.
+fn java_hash(text: &str) -> i32 {
+	text.encode_utf16().fold(0i32, |hash, unit| {
+		hash.wrapping_mul(31).wrapping_add(unit as i32)
+	})
+}
+
+/// Parses `key=value` lines the way Java's `Properties.load` does, skipping
+/// blank lines and `#` or `!` comments. A key ends at the first `=`, `:` or
+/// whitespace that is not escaped with a backslash, and escapes such as `\:`
+/// or `\u00e9` are resolved in both keys and values.
+fn parse_properties(text: &str) -> HashMap<String, String> {
+	text.lines()
+		.map(str::trim)
+		.filter(|line| {
+			!line.is_empty() && !line.starts_with('#') && !line.starts_with('!')
+		})
+		.map(|line| {
+			let mut escaped = false;
+			let end = line
+				.char_indices()
+				.find(|&(_, c)| {
+					let separator =
+						!escaped && (c == '=' || c == ':' || c.is_whitespace());
+					escaped = !escaped && c == '\\';
+					separator
+				})
+				.map_or(line.len(), |(i, _)| i);
+			let (key, value) = line.split_at(end);
+			let value = value.trim_start();
+			let value =
+				value.strip_prefix(['=', ':']).unwrap_or(value).trim_start();
+
+			(unescape(key), unescape(value))
+		})
+		.collect()
+}
+
+/// Resolves the backslash escapes of a properties file. Unknown escapes
+/// stand for the character itself.
+fn unescape(text: &str) -> String {
+	let mut unescaped = String::with_capacity(text.len());
+	let mut chars = text.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			unescaped.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('t') => unescaped.push('\t'),
+			Some('n') => unescaped.push('\n'),
+			Some('r') => unescaped.push('\r'),
+			Some('f') => unescaped.push('\u{c}'),
+			Some('u') => {
+				let digits: String = chars.by_ref().take(4).collect();
+				let c = u32::from_str_radix(&digits, 16)
+					.ok()
+					.and_then(char::from_u32)
+					.unwrap_or(char::REPLACEMENT_CHARACTER);
+				unescaped.push(c);
+			}
+			Some(c) => unescaped.push(c),
+			None => {}
+		}
+	}
+
+	unescaped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Error;
+
+	fn properties(entries: &[(&str, &str)]) -> HashMap<String, String> {
+		entries
+			.iter()
+			.map(|(key, value)| (key.to_string(), value.to_string()))
+			.collect()
+	}
+
+	#[test]
+	fn skips_comments_and_blank_lines() {
+		let text = "#Minecraft server properties\n\
+			! another comment\n\
+			\n\
+			  motd = Hello there  \n\
+			level-name:alpha\n\
+			white-list true\n\
+			level-seed=\n";
+
+		assert_eq!(
+			parse_properties(text),
+			properties(&[
+				("motd", "Hello there"),
+				("level-name", "alpha"),
+				("white-list", "true"),
+				("level-seed", ""),
+			])
+		);
+	}
+
+	#[test]
+	fn resolves_escapes() {
+		let text = "session-server=http\\://localhost\\:8080/check\n\
+			motd=Caf\\u00e9 \\#1\\tand\\\\more\n\
+			odd\\ key\\=name=value\n";
+
+		assert_eq!(
+			parse_properties(text),
+			properties(&[
+				("session-server", "http://localhost:8080/check"),
+				("motd", "Caf\u{e9} #1\tand\\more"),
+				("odd key=name", "value"),
+			])
+		);
+	}
+
+	#[test]
+	fn checks_values_against_their_range() {
+		let entries = properties(&[
+			("view-distance", "15"),
+			("max-players", "0"),
+			("server-port", "70000"),
+		]);
+
+		assert_eq!(
+			parse_in_range(&entries, "view-distance", 1..=15u32).unwrap(),
+			Some(15)
+		);
+		assert_eq!(
+			parse_in_range(&entries, "timeout", 1..=3600u32).unwrap(),
+			None
+		);
+		assert!(matches!(
+			parse_in_range(&entries, "max-players", 1..=1000u32),
+			Err(Error::PropertyOutOfRange {
+				key: "max-players",
+				value: 0,
+				min: 1,
+				max: 1000,
+			})
+		));
+		assert!(matches!(
+			parse_in_range(&entries, "server-port", 1..=u16::MAX),
+			Err(Error::InvalidProperty {
+				key: "server-port",
+				..
+			})
+		));
+	}
+
+	#[test]
+	fn hashes_seeds_like_java_strings() {
+		assert_eq!(java_hash(""), 0);
+		assert_eq!(java_hash("hello"), 99162322);
+		assert_eq!(java_hash("Minecraft"), -1595926131);
+		assert_eq!(java_hash("Glacier"), 1772835215);
+		// Characters outside the BMP hash as their two UTF-16 units.
+		assert_eq!(java_hash("\u{1F600}"), 1772899);
+	}
+}
@@ -0,0 +1,117 @@
+use crate::server::{Event, ENTITY_COUNTER};
+use irox_bits::Bits;
+use oxidized_alpha::{
+	bytes::ByteWriter,
+	packets::{ClientboundPacket, ServerboundPacket},
+};
+use std::{
+	io::{BufReader, Read, Write},
+	net::{Shutdown, SocketAddr, TcpStream},
+	sync::{atomic::Ordering, mpsc},
+};
+
+/// Reads packets from a stream. Packets are decoded a byte at a time, so
+/// the stream should be buffered.
+pub struct PacketSerializer<'a, Handle: Read> {
+	pub handle: &'a mut Handle,
+}
+
+impl<Handle: Read> Bits for PacketSerializer<'_, Handle> {
+	fn next_u8(&mut self) -> Result<Option<u8>, irox_bits::Error> {
+		let mut buffer = [0u8; 1];
+		self.handle.read_exact(&mut buffer).map_err(|_| {
+			irox_bits::Error::new(
+				irox_bits::BitsErrorKind::UnexpectedEof,
+				"Failed to read byte",
+			)
+		})?;
+
+		Ok(Some(buffer[0]))
+	}
+}
+
+impl<'a, Handle: Read> PacketSerializer<'a, Handle> {
+	pub fn new(handle: &'a mut Handle) -> Self {
+		Self { handle }
+	}
+}
+
+/// Starts the reader and writer threads for a freshly accepted client.
+///
+/// The reader decodes packets and forwards them to the server thread as
+/// [`Event`]s, while the writer drains the batches of packets the server
+/// flushes at the end of every tick. Closing the outbound queue shuts the
+/// socket down, which in turn ends the reader.
+pub fn spawn(
+	stream: TcpStream,
+	address: SocketAddr,
+	events: mpsc::Sender<Event>,
+) -> std::io::Result<()> {
+	let id = ENTITY_COUNTER.fetch_add(1, Ordering::Relaxed);
+	// Packets are already batched per tick, so waiting to coalesce them
+	// further would only add latency.
+	stream.set_nodelay(true)?;
+	let mut write_stream = stream.try_clone()?;
+	let (outbound, queue) = mpsc::channel::<Vec<ClientboundPacket>>();
+
+	let writer = std::thread::spawn(move || {
+		// Each batch is encoded into one buffer and written with a single
+		// call, rather than one write per byte.
+		let mut buffer = ByteWriter::new();
+
+		for packets in queue {
+			buffer.clear();
+			let encoded = packets
+				.iter()
+				.try_for_each(|packet| packet.encode(&mut buffer));
+			if let Err(err) = encoded {
+				tracing::debug!("session {id}: encoding failed: {err}");
+				break;
+			}
+			if let Err(err) = write_stream.write_all(buffer.as_bytes()) {
+				tracing::debug!("session {id}: write failed: {err}");
+				break;
+			}
+		}
+
+		let _ = write_stream.shutdown(Shutdown::Both);
+	});
+
+	let connected = Event::Connected {
+		id,
+		address,
+		outbound,
+		writer,
+	};
+	if events.send(connected).is_err() {
+		return Ok(());
+	}
+
+	std::thread::spawn(move || {
+		let mut stream = BufReader::new(stream);
+		let mut serializer = PacketSerializer::new(&mut stream);
+
+		// Packets carry no length, so after an unknown one the rest of the
+		// stream cannot be framed and the connection has to end.
+		let reason = loop {
+			let packet = match ServerboundPacket::decode(&mut serializer) {
+				Ok(ServerboundPacket::KickOrDisconnect { reason }) => {
+					break reason;
+				}
+				Ok(packet) => packet,
+				Err(err) => {
+					tracing::debug!("session {id}: read failed: {err}");
+					break err.to_string();
+				}
+			};
+
+			if events.send(Event::Packet { id, packet }).is_err() {
+				return;
+			}
+		};
+
+		let _ = events.send(Event::Disconnected { id, reason });
+	});
+
+	Ok(())
+}
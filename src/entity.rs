@@ -0,0 +1,129 @@
+//! Entities other than players, which the server simulates itself.
+
+use crate::{
+	math,
+	packets::{to_fixed_point, ClientboundPacket},
+	ItemStack, Player,
+};
+
+/// How long a dropped item lies around before it disappears, in ticks.
+pub const ITEM_LIFETIME: u32 = 6000;
+/// How far an item falls each tick, in blocks per tick.
+const GRAVITY: f64 = 0.04;
+/// How much of its speed an item keeps each tick while in the air.
+const DRAG: f64 = 0.98;
+/// How much of its horizontal speed an item keeps each tick while sliding
+/// along the ground.
+const GROUND_FRICTION: f64 = 0.6 * DRAG;
+/// Half the width of an item's bounding box.
+const ITEM_HALF_SIZE: f64 = 0.125;
+/// How far outside a player's bounding box they still pick items up,
+/// horizontally and vertically.
+const PICKUP_REACH: (f64, f64) = (1.0, 0.5);
+
+/// A stack of items lying in the world, waiting to be picked up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemEntity {
+	pub id: i32,
+	pub item: ItemStack,
+	pub x: f64,
+	pub y: f64,
+	pub z: f64,
+	/// Blocks per tick along each axis.
+	pub velocity_x: f64,
+	pub velocity_y: f64,
+	pub velocity_z: f64,
+	/// How many ticks the item has existed for.
+	pub age: u32,
+	/// The age the item has to reach before anyone can pick it up.
+	pub pickup_delay: u32,
+}
+
+impl ItemEntity {
+	/// Moves the item by one tick, letting it fall until it lands on a
+	/// block that `is_solid` says stops it.
+	pub fn tick(&mut self, mut is_solid: impl FnMut(i32, i32, i32) -> bool) {
+		self.age += 1;
+		self.velocity_y -= GRAVITY;
+
+		let (block_y, block_z) = (math::floor(self.y), math::floor(self.z));
+		let x = self.x + self.velocity_x;
+		if is_solid(math::floor(x), block_y, block_z) {
+			self.velocity_x = 0.0;
+		} else {
+			self.x = x;
+		}
+		let block_x = math::floor(self.x);
+		let z = self.z + self.velocity_z;
+		if is_solid(block_x, block_y, math::floor(z)) {
+			self.velocity_z = 0.0;
+		} else {
+			self.z = z;
+		}
+		let block_z = math::floor(self.z);
+		let y = self.y + self.velocity_y;
+		let on_ground = is_solid(block_x, math::floor(y), block_z);
+		if on_ground {
+			if self.velocity_y < 0.0 {
+				self.y = math::floor(y) as f64 + 1.0;
+			}
+			self.velocity_y = 0.0;
+		} else {
+			self.y = y;
+		}
+
+		let friction = if on_ground { GROUND_FRICTION } else { DRAG };
+		self.velocity_x *= friction;
+		self.velocity_y *= DRAG;
+		self.velocity_z *= friction;
+	}
+
+	/// Whether the item has lain around for too long, or fell out of the
+	/// world.
+	pub fn is_expired(&self) -> bool {
+		self.age >= ITEM_LIFETIME || self.y < -64.0
+	}
+
+	pub fn can_be_picked_up(&self) -> bool {
+		self.age >= self.pickup_delay
+	}
+
+	/// Whether the item is close enough for the player to pick it up.
+	pub fn is_near(&self, player: &Player) -> bool {
+		const PLAYER_HALF_WIDTH: f64 = 0.3;
+		const PLAYER_HEIGHT: f64 = 1.8;
+
+		let (horizontal, vertical) = PICKUP_REACH;
+		let reach = PLAYER_HALF_WIDTH + horizontal + ITEM_HALF_SIZE;
+		let (dx, dz) = (self.x - player.x, self.z - player.z);
+		dx > -reach
+			&& dx < reach
+			&& dz > -reach
+			&& dz < reach
+			&& self.y + 2.0 * ITEM_HALF_SIZE > player.y - vertical
+			&& self.y < player.y + PLAYER_HEIGHT + vertical
+	}
+
+	pub fn chunk_position(&self) -> (i32, i32) {
+		(math::floor(self.x) >> 4, math::floor(self.z) >> 4)
+	}
+
+	/// The PICKUP_SPAWN packet that shows the item to a client, which
+	/// simulates its fall from there. The last three fields carry the
+	/// velocity in 128ths of a block per tick.
+	pub fn to_packet(&self) -> ClientboundPacket {
+		let velocity = |velocity: f64| (velocity * 128.0) as i8;
+
+		ClientboundPacket::PickupSpawn {
+			entity_id: self.id,
+			item_id: self.item.id,
+			count: self.item.count,
+			x: to_fixed_point(self.x),
+			y: to_fixed_point(self.y),
+			z: to_fixed_point(self.z),
+			rotation: velocity(self.velocity_x),
+			pitch: velocity(self.velocity_y),
+			roll: velocity(self.velocity_z),
+		}
+	}
+}
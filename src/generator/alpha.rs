@@ -0,0 +1,686 @@
+//! A port of the Alpha terrain generator.
+//!
+//! Generation happens in two passes, as in the original game. [`generate`]
+//! shapes a chunk on its own: density noise decides stone, water fills
+//! everything below sea level, beach noise lays sand and gravel along the
+//! shore, and caves are carved through it. [`populate`] then places ores,
+//! trees and plants, which spill over into neighbouring chunks, so it runs
+//! once the chunks to the east, south and south-east exist too.
+//!
+//! Big trees and dungeons are not generated yet.
+//!
+//! [`generate`]: AlphaGenerator::generate
+//! [`populate`]: AlphaGenerator::populate
+
+use super::{features, noise::OctaveNoise, Region, WorldGenerator, PI};
+use crate::{
+	blocks,
+	chunk::{block_index, CHUNK_HEIGHT, CHUNK_WIDTH},
+	light,
+	math::{cos, floor, sin},
+	random::JavaRandom,
+	Chunk,
+};
+
+pub const SEA_LEVEL: i32 = 64;
+
+/// A quarter turn, with the same rounding as [`PI`].
+#[allow(clippy::approx_constant)]
+const HALF_PI: f32 = 1.570796;
+
+/// How far, in chunks, a cave system can reach from the chunk it starts in.
+const CAVE_RANGE: i32 = 8;
+
+/// Density noise is sampled every 4 blocks horizontally and 8 vertically,
+/// then interpolated in between.
+const CELL_WIDTH: usize = 4;
+const CELL_HEIGHT: usize = 8;
+const CELLS_X: usize = CHUNK_WIDTH / CELL_WIDTH + 1;
+const CELLS_Y: usize = CHUNK_HEIGHT / CELL_HEIGHT + 1;
+
+pub struct AlphaGenerator {
+	seed: i64,
+	low: OctaveNoise,
+	high: OctaveNoise,
+	selector: OctaveNoise,
+	beach: OctaveNoise,
+	surface_depth: OctaveNoise,
+	scale: OctaveNoise,
+	depth: OctaveNoise,
+	trees: OctaveNoise,
+}
+
+impl AlphaGenerator {
+	pub fn new(seed: i64) -> Self {
+		let mut random = JavaRandom::new(seed);
+
+		Self {
+			seed,
+			low: OctaveNoise::new(&mut random, 16),
+			high: OctaveNoise::new(&mut random, 16),
+			selector: OctaveNoise::new(&mut random, 8),
+			beach: OctaveNoise::new(&mut random, 4),
+			surface_depth: OctaveNoise::new(&mut random, 4),
+			scale: OctaveNoise::new(&mut random, 10),
+			depth: OctaveNoise::new(&mut random, 16),
+			trees: OctaveNoise::new(&mut random, 8),
+		}
+	}
+
+	pub fn seed(&self) -> i64 {
+		self.seed
+	}
+
+	/// Samples the terrain density on the coarse grid: positive values are
+	/// solid.
+	fn density(&self, x: i32, z: i32) -> [f64; CELLS_X * CELLS_Y * CELLS_X] {
+		const HORIZONTAL: f64 = 684.412;
+		const VERTICAL: f64 = 684.412;
+
+		let origin = ((x * 4) as f64, (z * 4) as f64);
+		let columns = (CELLS_X, CELLS_X);
+		let origin_3d = (origin.0, 0.0, origin.1);
+		let size = (CELLS_X, CELLS_Y, CELLS_X);
+
+		let scale = self.scale.grid_2d(origin, columns, (1.0, 1.0));
+		let depth = self.depth.grid_2d(origin, columns, (200.0, 200.0));
+		let selector = self.selector.grid(
+			origin_3d,
+			size,
+			(HORIZONTAL / 80.0, VERTICAL / 160.0, HORIZONTAL / 80.0),
+		);
+		let low =
+			self.low
+				.grid(origin_3d, size, (HORIZONTAL, VERTICAL, HORIZONTAL));
+		let high =
+			self.high
+				.grid(origin_3d, size, (HORIZONTAL, VERTICAL, HORIZONTAL));
+
+		let mut density = [0.0; CELLS_X * CELLS_Y * CELLS_X];
+		for column in 0..CELLS_X * CELLS_X {
+			let mut scale = ((scale[column] + 256.0) / 512.0).min(1.0);
+			let mut depth = (depth[column] / 8000.0).abs() * 3.0 - 3.0;
+			if depth < 0.0 {
+				depth = (depth / 2.0).max(-1.0) / 1.4 / 2.0;
+				scale = 0.0;
+			} else {
+				depth = depth.min(1.0) / 6.0;
+			}
+			scale += 0.5;
+			depth = depth * CELLS_Y as f64 / 16.0;
+			let height = CELLS_Y as f64 / 2.0 + depth * 4.0;
+
+			for y in 0..CELLS_Y {
+				let index = column * CELLS_Y + y;
+
+				let mut falloff = (y as f64 - height) * 12.0 / scale;
+				if falloff < 0.0 {
+					falloff *= 4.0;
+				}
+
+				let low = low[index] / 512.0;
+				let high = high[index] / 512.0;
+				let selector = (selector[index] / 10.0 + 1.0) / 2.0;
+				let mut value = if selector < 0.0 {
+					low
+				} else if selector > 1.0 {
+					high
+				} else {
+					low + (high - low) * selector
+				};
+				value -= falloff;
+
+				// Close the terrain off towards the top of the world.
+				if y > CELLS_Y - 4 {
+					let blend = ((y - (CELLS_Y - 4)) as f32 / 3.0) as f64;
+					value = value * (1.0 - blend) - 10.0 * blend;
+				}
+
+				density[index] = value;
+			}
+		}
+
+		density
+	}
+
+	/// Fills the chunk with stone where the interpolated density is positive
+	/// and with water below sea level elsewhere.
+	fn shape_terrain(&self, chunk: &mut Chunk) {
+		let density = self.density(chunk.x, chunk.z);
+		let at = |x: usize, y: usize, z: usize| {
+			density[(x * CELLS_X + z) * CELLS_Y + y]
+		};
+
+		for cell_x in 0..CELLS_X - 1 {
+			for cell_z in 0..CELLS_X - 1 {
+				for cell_y in 0..CELLS_Y - 1 {
+					let mut corners = [
+						at(cell_x, cell_y, cell_z),
+						at(cell_x, cell_y, cell_z + 1),
+						at(cell_x + 1, cell_y, cell_z),
+						at(cell_x + 1, cell_y, cell_z + 1),
+					];
+					let steps = [
+						(at(cell_x, cell_y + 1, cell_z) - corners[0])
+							/ CELL_HEIGHT as f64,
+						(at(cell_x, cell_y + 1, cell_z + 1) - corners[1])
+							/ CELL_HEIGHT as f64,
+						(at(cell_x + 1, cell_y + 1, cell_z) - corners[2])
+							/ CELL_HEIGHT as f64,
+						(at(cell_x + 1, cell_y + 1, cell_z + 1) - corners[3])
+							/ CELL_HEIGHT as f64,
+					];
+
+					for dy in 0..CELL_HEIGHT {
+						let y = cell_y * CELL_HEIGHT + dy;
+						let mut near = corners[0];
+						let mut far = corners[1];
+						let near_step =
+							(corners[2] - corners[0]) / CELL_WIDTH as f64;
+						let far_step =
+							(corners[3] - corners[1]) / CELL_WIDTH as f64;
+
+						for dx in 0..CELL_WIDTH {
+							let x = cell_x * CELL_WIDTH + dx;
+							let mut value = near;
+							let step = (far - near) / CELL_WIDTH as f64;
+
+							for dz in 0..CELL_WIDTH {
+								let z = cell_z * CELL_WIDTH + dz;
+								let block = if value > 0.0 {
+									blocks::STONE
+								} else if (y as i32) < SEA_LEVEL {
+									blocks::WATER
+								} else {
+									blocks::AIR
+								};
+								chunk.set_block(x, y, z, block);
+								value += step;
+							}
+
+							near += near_step;
+							far += far_step;
+						}
+
+						for (corner, step) in corners.iter_mut().zip(steps) {
+							*corner += step;
+						}
+					}
+				}
+			}
+		}
+	}
+
+	/// Turns the top of the stone into grass and dirt, with sand and gravel
+	/// beaches near sea level, and lays bedrock at the bottom.
+	fn replace_surface(&self, chunk: &mut Chunk, random: &mut JavaRandom) {
+		const SCALE: f64 = 1.0 / 32.0;
+
+		let origin = ((chunk.x * 16) as f64, (chunk.z * 16) as f64);
+		let sand = self.beach.grid(
+			(origin.0, origin.1, 0.0),
+			(16, 16, 1),
+			(SCALE, SCALE, 1.0),
+		);
+		let gravel = self.beach.grid(
+			(origin.0, 109.0134, origin.1),
+			(16, 1, 16),
+			(SCALE, 1.0, SCALE),
+		);
+		let depth = self.surface_depth.grid(
+			(origin.0, origin.1, 0.0),
+			(16, 16, 1),
+			(SCALE * 2.0, SCALE * 2.0, SCALE * 2.0),
+		);
+
+		for x in 0..CHUNK_WIDTH {
+			for z in 0..CHUNK_WIDTH {
+				// The noise is indexed transposed, as in the original.
+				let noise = x + z * 16;
+				let sand = sand[noise] + random.next_double() * 0.2 > 0.0;
+				let gravel = gravel[noise] + random.next_double() * 0.2 > 3.0;
+				let depth = (depth[noise] / 3.0
+					+ 3.0 + random.next_double() * 0.25) as i32;
+
+				let mut remaining = -1;
+				let mut top = blocks::GRASS;
+				let mut filler = blocks::DIRT;
+
+				for y in (0..CHUNK_HEIGHT).rev() {
+					let index = block_index(x, y, z);
+					if y as i32 <= random.next_int(5) {
+						chunk.blocks[index] = blocks::BEDROCK;
+						continue;
+					}
+
+					let block = chunk.blocks[index];
+					if block == blocks::AIR {
+						remaining = -1;
+						continue;
+					}
+					if block != blocks::STONE {
+						continue;
+					}
+
+					if remaining == -1 {
+						let y = y as i32;
+						if depth <= 0 {
+							top = blocks::AIR;
+							filler = blocks::STONE;
+						} else if (SEA_LEVEL - 4..=SEA_LEVEL + 1).contains(&y) {
+							top = blocks::GRASS;
+							filler = blocks::DIRT;
+							if gravel {
+								top = blocks::AIR;
+								filler = blocks::GRAVEL;
+							}
+							if sand {
+								top = blocks::SAND;
+								filler = blocks::SAND;
+							}
+						}
+						if y < SEA_LEVEL && top == blocks::AIR {
+							top = blocks::WATER;
+						}
+
+						remaining = depth;
+						chunk.blocks[index] =
+							if y >= SEA_LEVEL - 1 { top } else { filler };
+					} else if remaining > 0 {
+						remaining -= 1;
+						chunk.blocks[index] = filler;
+					}
+				}
+			}
+		}
+	}
+
+	/// Carves the caves of every chunk within range that reach into this one.
+	fn carve_caves(&self, chunk: &mut Chunk) {
+		let mut random = JavaRandom::new(self.seed);
+		let a = random.next_long() / 2 * 2 + 1;
+		let b = random.next_long() / 2 * 2 + 1;
+
+		for x in chunk.x - CAVE_RANGE..=chunk.x + CAVE_RANGE {
+			for z in chunk.z - CAVE_RANGE..=chunk.z + CAVE_RANGE {
+				random.set_seed(
+					(x as i64)
+						.wrapping_mul(a)
+						.wrapping_add((z as i64).wrapping_mul(b))
+						^ self.seed,
+				);
+				Caves {
+					chunk: &mut *chunk,
+					random: &mut random,
+				}
+				.start(x, z);
+			}
+		}
+	}
+}
+
+impl WorldGenerator for AlphaGenerator {
+	/// Generates the terrain of a chunk, without the features added when it
+	/// is populated.
+	fn generate(&self, x: i32, z: i32) -> Chunk {
+		let mut random = JavaRandom::new(
+			(x as i64)
+				.wrapping_mul(341_873_128_712)
+				.wrapping_add((z as i64).wrapping_mul(132_897_987_541)),
+		);
+		let mut chunk = Chunk::new(x, z);
+
+		self.shape_terrain(&mut chunk);
+		self.replace_surface(&mut chunk, &mut random);
+		self.carve_caves(&mut chunk);
+		light::light_chunk(&mut chunk);
+
+		chunk
+	}
+
+	/// Places ores, trees and plants around the chunk at `(x, z)`.
+	fn populate(&self, chunks: &mut [&mut Chunk], x: i32, z: i32) {
+		let mut region = Region { chunks };
+		let mut random = JavaRandom::new(self.seed);
+		let a = random.next_long() / 2 * 2 + 1;
+		let b = random.next_long() / 2 * 2 + 1;
+		random.set_seed(
+			(x as i64)
+				.wrapping_mul(a)
+				.wrapping_add((z as i64).wrapping_mul(b))
+				^ self.seed,
+		);
+
+		let (block_x, block_z) = (x * 16, z * 16);
+		let region = &mut region;
+		let random = &mut random;
+		let spot = |random: &mut JavaRandom, height: i32, offset: i32| {
+			let x = block_x + random.next_int(16) + offset;
+			let y = random.next_int(height);
+			let z = block_z + random.next_int(16) + offset;
+			(x, y, z)
+		};
+
+		for _ in 0..10 {
+			let position = spot(random, 128, 0);
+			features::clay(region, random, 32, position);
+		}
+		for (ore, size, attempts, height) in [
+			(blocks::DIRT, 32, 20, 128),
+			(blocks::GRAVEL, 32, 10, 128),
+			(blocks::COAL_ORE, 16, 20, 128),
+			(blocks::IRON_ORE, 8, 20, 64),
+			(blocks::GOLD_ORE, 8, 2, 32),
+			(blocks::REDSTONE_ORE, 7, 8, 16),
+			(blocks::DIAMOND_ORE, 7, 1, 16),
+		] {
+			for _ in 0..attempts {
+				let position = spot(random, height, 0);
+				features::minable(region, random, ore, size, position);
+			}
+		}
+
+		let density = self
+			.trees
+			.sample_2d(block_x as f64 * 0.5, block_z as f64 * 0.5);
+		let mut trees =
+			((density / 8.0 + random.next_double() * 4.0 + 4.0) / 3.0) as i32;
+		if trees < 0 {
+			trees = 0;
+		}
+		if random.next_int(10) == 0 {
+			trees += 1;
+		}
+		for _ in 0..trees {
+			let x = block_x + random.next_int(16) + 8;
+			let z = block_z + random.next_int(16) + 8;
+			let y = region.height(x, z);
+			features::tree(region, random, (x, y, z));
+		}
+
+		for _ in 0..2 {
+			let position = spot(random, 128, 8);
+			features::plants(region, random, blocks::YELLOW_FLOWER, position);
+		}
+		for (plant, chance) in [
+			(blocks::RED_ROSE, 2),
+			(blocks::BROWN_MUSHROOM, 4),
+			(blocks::RED_MUSHROOM, 8),
+		] {
+			if random.next_int(chance) == 0 {
+				let position = spot(random, 128, 8);
+				features::plants(region, random, plant, position);
+			}
+		}
+		for _ in 0..10 {
+			let position = spot(random, 128, 8);
+			features::reeds(region, random, position);
+		}
+		if random.next_int(32) == 0 {
+			let position = spot(random, 128, 8);
+			features::pumpkins(region, random, position);
+		}
+
+		for chunk in region.chunks.iter_mut() {
+			if chunk.x == x && chunk.z == z {
+				chunk.populated = true;
+			}
+			light::light_chunk(chunk);
+		}
+	}
+
+	/// Alpha spawns players on a sandy shore.
+	fn is_spawn_block(&self, block: u8) -> bool {
+		block == blocks::SAND
+	}
+}
+
+/// Carves the cave systems starting in one chunk into another.
+struct Caves<'a> {
+	chunk: &'a mut Chunk,
+	random: &'a mut JavaRandom,
+}
+
+/// A point along a cave tunnel.
+#[derive(Clone, Copy)]
+struct Tunnel {
+	x: f64,
+	y: f64,
+	z: f64,
+	width: f32,
+	yaw: f32,
+	pitch: f32,
+	step: i32,
+	length: i32,
+	vertical_scale: f64,
+}
+
+impl Caves<'_> {
+	fn start(&mut self, chunk_x: i32, chunk_z: i32) {
+		let bound = self.random.next_int(40) + 1;
+		let bound = self.random.next_int(bound) + 1;
+		let mut systems = self.random.next_int(bound);
+		if self.random.next_int(15) != 0 {
+			systems = 0;
+		}
+
+		for _ in 0..systems {
+			let x = (chunk_x * 16 + self.random.next_int(16)) as f64;
+			let bound = self.random.next_int(120) + 8;
+			let y = self.random.next_int(bound) as f64;
+			let z = (chunk_z * 16 + self.random.next_int(16)) as f64;
+
+			let mut tunnels = 1;
+			if self.random.next_int(4) == 0 {
+				// A large round room, with a few extra tunnels leading off.
+				let width = 1.0 + self.random.next_float() * 6.0;
+				self.tunnel(Tunnel {
+					x,
+					y,
+					z,
+					width,
+					yaw: 0.0,
+					pitch: 0.0,
+					step: -1,
+					length: -1,
+					vertical_scale: 0.5,
+				});
+				tunnels += self.random.next_int(4);
+			}
+
+			for _ in 0..tunnels {
+				let yaw = self.random.next_float() * PI * 2.0;
+				let pitch = (self.random.next_float() - 0.5) * 2.0 / 8.0;
+				let width =
+					self.random.next_float() * 2.0 + self.random.next_float();
+				self.tunnel(Tunnel {
+					x,
+					y,
+					z,
+					width,
+					yaw,
+					pitch,
+					step: 0,
+					length: 0,
+					vertical_scale: 1.0,
+				});
+			}
+		}
+	}
+
+	/// Walks a tunnel, hollowing out an ellipsoid at each step that falls
+	/// inside this chunk. A `step` of -1 carves a single room.
+	fn tunnel(&mut self, mut tunnel: Tunnel) {
+		let center_x = (self.chunk.x * 16 + 8) as f64;
+		let center_z = (self.chunk.z * 16 + 8) as f64;
+		let mut yaw_change = 0.0f32;
+		let mut pitch_change = 0.0f32;
+		let mut random = JavaRandom::new(self.random.next_long());
+
+		if tunnel.length <= 0 {
+			let range = CAVE_RANGE * 16 - 16;
+			tunnel.length = range - random.next_int(range / 4);
+		}
+		let room = tunnel.step == -1;
+		if room {
+			tunnel.step = tunnel.length / 2;
+		}
+		let branch_at = random.next_int(tunnel.length / 2) + tunnel.length / 4;
+		let steep = random.next_int(6) == 0;
+
+		for step in tunnel.step..tunnel.length {
+			let radius = 1.5
+				+ (sin(step as f32 * PI / tunnel.length as f32) * tunnel.width)
+					as f64;
+			let vertical_radius = radius * tunnel.vertical_scale;
+
+			let horizontal = cos(tunnel.pitch);
+			tunnel.x += (cos(tunnel.yaw) * horizontal) as f64;
+			tunnel.y += sin(tunnel.pitch) as f64;
+			tunnel.z += (sin(tunnel.yaw) * horizontal) as f64;
+
+			tunnel.pitch *= if steep { 0.92 } else { 0.7 };
+			tunnel.pitch += pitch_change * 0.1;
+			tunnel.yaw += yaw_change * 0.1;
+			pitch_change *= 0.9;
+			yaw_change *= 0.75;
+			pitch_change += (random.next_float() - random.next_float())
+				* random.next_float()
+				* 2.0;
+			yaw_change += (random.next_float() - random.next_float())
+				* random.next_float()
+				* 4.0;
+
+			if !room && step == branch_at && tunnel.width > 1.0 {
+				for turn in [-HALF_PI, HALF_PI] {
+					let width = random.next_float() * 0.5 + 0.5;
+					self.tunnel(Tunnel {
+						width,
+						yaw: tunnel.yaw + turn,
+						pitch: tunnel.pitch / 3.0,
+						step,
+						vertical_scale: 1.0,
+						..tunnel
+					});
+				}
+				return;
+			}
+			if !room && random.next_int(4) == 0 {
+				continue;
+			}
+
+			let dx = tunnel.x - center_x;
+			let dz = tunnel.z - center_z;
+			let remaining = (tunnel.length - step) as f64;
+			let reach = (tunnel.width + 2.0 + 16.0) as f64;
+			if dx * dx + dz * dz - remaining * remaining > reach * reach {
+				return;
+			}
+
+			// A room that misses this chunk or hits water keeps drifting
+			// until it carves somewhere, as in the original.
+			let margin = 16.0 + radius * 2.0;
+			if tunnel.x < center_x - margin
+				|| tunnel.z < center_z - margin
+				|| tunnel.x > center_x + margin
+				|| tunnel.z > center_z + margin
+				|| !self.hollow(&tunnel, radius, vertical_radius)
+			{
+				continue;
+			}
+
+			if room {
+				break;
+			}
+		}
+	}
+
+	/// Hollows out the ellipsoid around a point of a tunnel. Returns false
+	/// without carving anything if that would breach water.
+	fn hollow(
+		&mut self,
+		tunnel: &Tunnel,
+		radius: f64,
+		vertical_radius: f64,
+	) -> bool {
+		let (base_x, base_z) = (self.chunk.x * 16, self.chunk.z * 16);
+		let min_x = (floor(tunnel.x - radius) - base_x - 1).max(0);
+		let max_x = (floor(tunnel.x + radius) - base_x + 1).min(16);
+		let min_y = (floor(tunnel.y - vertical_radius) - 1).max(1);
+		let max_y = (floor(tunnel.y + vertical_radius) + 1).min(120);
+		let min_z = (floor(tunnel.z - radius) - base_z - 1).max(0);
+		let max_z = (floor(tunnel.z + radius) - base_z + 1).min(16);
+
+		// Only the shell of the box can touch water that would flood in.
+		for x in min_x..max_x {
+			for z in min_z..max_z {
+				let mut y = max_y + 1;
+				while y >= min_y - 1 {
+					if (0..CHUNK_HEIGHT as i32).contains(&y) {
+						let block = self
+							.chunk
+							.get_block(x as usize, y as usize, z as usize);
+						if matches!(
+							block,
+							blocks::WATER | blocks::FLOWING_WATER
+						) {
+							return false;
+						}
+						if y != min_y - 1
+							&& x != min_x && x != max_x - 1
+							&& z != min_z && z != max_z - 1
+						{
+							y = min_y;
+						}
+					}
+					y -= 1;
+				}
+			}
+		}
+
+		for x in min_x..max_x {
+			let dx = ((x + base_x) as f64 + 0.5 - tunnel.x) / radius;
+			for z in min_z..max_z {
+				let dz = ((z + base_z) as f64 + 0.5 - tunnel.z) / radius;
+				if dx * dx + dz * dz >= 1.0 {
+					continue;
+				}
+
+				let mut grass = false;
+				for y in (min_y..max_y).rev() {
+					let dy = (y as f64 + 0.5 - tunnel.y) / vertical_radius;
+					if dy <= -0.7 || dx * dx + dy * dy + dz * dz >= 1.0 {
+						continue;
+					}
+
+					// The original tests one block below the one it carves.
+					let index =
+						block_index(x as usize, y as usize + 1, z as usize);
+					let block = self.chunk.blocks[index];
+					if block == blocks::GRASS {
+						grass = true;
+					}
+					if !matches!(
+						block,
+						blocks::STONE | blocks::DIRT | blocks::GRASS
+					) {
+						continue;
+					}
+
+					if y < 10 {
+						self.chunk.blocks[index] = blocks::FLOWING_LAVA;
+					} else {
+						self.chunk.blocks[index] = blocks::AIR;
+						// Keep the surface green where a cave opens up.
+						if grass && self.chunk.blocks[index - 1] == blocks::DIRT
+						{
+							self.chunk.blocks[index - 1] = blocks::GRASS;
+						}
+					}
+				}
+			}
+		}
+
+		true
+	}
+}
@@ -0,0 +1,61 @@
+use super::{parse_blocks, WorldGenerator};
+use crate::{
+	blocks, chunk::CHUNK_WIDTH, light, Chunk, InvalidGeneratorSettingsSnafu,
+	Result,
+};
+use alloc::string::ToString;
+use snafu::OptionExt;
+
+/// The height of the ground, bedrock included.
+pub const HEIGHT: usize = 64;
+
+/// Fills alternating chunks with two different blocks, so chunk borders are
+/// easy to see.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckerboardGenerator {
+	blocks: [u8; 2],
+}
+
+impl Default for CheckerboardGenerator {
+	fn default() -> Self {
+		Self {
+			blocks: [blocks::STONE, blocks::PLANKS],
+		}
+	}
+}
+
+impl CheckerboardGenerator {
+	pub fn new(blocks: [u8; 2]) -> Self {
+		Self { blocks }
+	}
+
+	/// Parses the two block ids, as in `1,5`.
+	pub fn from_preset(preset: &str) -> Result<Self> {
+		let blocks = parse_blocks(preset)
+			.and_then(|blocks| blocks.try_into().ok())
+			.context(InvalidGeneratorSettingsSnafu {
+				settings: preset.to_string(),
+			})?;
+
+		Ok(Self { blocks })
+	}
+}
+
+impl WorldGenerator for CheckerboardGenerator {
+	fn generate(&self, x: i32, z: i32) -> Chunk {
+		let block = self.blocks[((x + z) & 1) as usize];
+
+		let mut chunk = Chunk::new(x, z);
+		for bx in 0..CHUNK_WIDTH {
+			for bz in 0..CHUNK_WIDTH {
+				chunk.set_block(bx, 0, bz, blocks::BEDROCK);
+				for y in 1..HEIGHT {
+					chunk.set_block(bx, y, bz, block);
+				}
+			}
+		}
+		light::light_chunk(&mut chunk);
+
+		chunk
+	}
+}
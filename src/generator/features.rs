@@ -0,0 +1,252 @@
+//! The decorations placed while populating a chunk, ported from the Alpha
+//! `WorldGen*` classes so the same seed places them in the same spots.
+
+use super::{Region, PI};
+use crate::{
+	blocks,
+	math::{cos, floor, sin},
+	random::JavaRandom,
+};
+
+/// Carves a vein of `size` blocks of `ore` through stone.
+pub fn minable(
+	region: &mut Region,
+	random: &mut JavaRandom,
+	ore: u8,
+	size: i32,
+	(x, y, z): (i32, i32, i32),
+) {
+	vein(region, random, size, (x, y, z), |region, x, y, z| {
+		if region.get_block(x, y, z) == blocks::STONE {
+			region.set_block(x, y, z, ore);
+		}
+	});
+}
+
+/// Turns sand into clay, starting from a spot under water.
+pub fn clay(
+	region: &mut Region,
+	random: &mut JavaRandom,
+	size: i32,
+	(x, y, z): (i32, i32, i32),
+) {
+	if !region.is_water(x, y, z) {
+		return;
+	}
+
+	vein(region, random, size, (x, y, z), |region, x, y, z| {
+		if region.get_block(x, y, z) == blocks::SAND {
+			region.set_block(x, y, z, blocks::CLAY);
+		}
+	});
+}
+
+/// Walks a line of overlapping ellipsoids, calling `place` for every block
+/// inside one of them.
+fn vein(
+	region: &mut Region,
+	random: &mut JavaRandom,
+	size: i32,
+	(x, y, z): (i32, i32, i32),
+	place: impl Fn(&mut Region, i32, i32, i32),
+) {
+	let angle = random.next_float() * PI;
+	let spread_x = sin(angle) * size as f32 / 8.0;
+	let spread_z = cos(angle) * size as f32 / 8.0;
+	let start_x = ((x + 8) as f32 + spread_x) as f64;
+	let end_x = ((x + 8) as f32 - spread_x) as f64;
+	let start_z = ((z + 8) as f32 + spread_z) as f64;
+	let end_z = ((z + 8) as f32 - spread_z) as f64;
+	let start_y = (y + random.next_int(3) - 2) as f64;
+	let end_y = (y + random.next_int(3) - 2) as f64;
+
+	for step in 0..=size {
+		let center_x = start_x + (end_x - start_x) * step as f64 / size as f64;
+		let center_y = start_y + (end_y - start_y) * step as f64 / size as f64;
+		let center_z = start_z + (end_z - start_z) * step as f64 / size as f64;
+
+		let scale = random.next_double() * size as f64 / 16.0;
+		let bulge = (sin(step as f32 * PI / size as f32) + 1.0) as f64;
+		let radius = (bulge * scale + 1.0) / 2.0;
+
+		for bx in floor(center_x - radius)..=floor(center_x + radius) {
+			let dx = (bx as f64 + 0.5 - center_x) / radius;
+			if dx * dx >= 1.0 {
+				continue;
+			}
+			for by in floor(center_y - radius)..=floor(center_y + radius) {
+				let dy = (by as f64 + 0.5 - center_y) / radius;
+				if dx * dx + dy * dy >= 1.0 {
+					continue;
+				}
+				for bz in floor(center_z - radius)..=floor(center_z + radius) {
+					let dz = (bz as f64 + 0.5 - center_z) / radius;
+					if dx * dx + dy * dy + dz * dz < 1.0 {
+						place(region, bx, by, bz);
+					}
+				}
+			}
+		}
+	}
+}
+
+/// Grows a small oak on grass or dirt, with `(x, y, z)` the first log.
+pub fn tree(
+	region: &mut Region,
+	random: &mut JavaRandom,
+	(x, y, z): (i32, i32, i32),
+) {
+	let height = random.next_int(3) + 4;
+	if y < 1 || y + height + 1 > 128 {
+		return;
+	}
+
+	// The trunk and canopy need room to grow.
+	for by in y..=y + 1 + height {
+		let radius = if by == y {
+			0
+		} else if by >= y + 1 + height - 2 {
+			2
+		} else {
+			1
+		};
+		for bx in x - radius..=x + radius {
+			for bz in z - radius..=z + radius {
+				if !matches!(
+					region.get_block(bx, by, bz),
+					blocks::AIR | blocks::LEAVES
+				) {
+					return;
+				}
+			}
+		}
+	}
+
+	if !matches!(region.get_block(x, y - 1, z), blocks::GRASS | blocks::DIRT)
+		|| y >= 128 - height - 1
+	{
+		return;
+	}
+	region.set_block(x, y - 1, z, blocks::DIRT);
+
+	for by in y - 3 + height..=y + height {
+		let above_top = by - (y + height);
+		let radius = 1 - above_top / 2;
+		for bx in x - radius..=x + radius {
+			for bz in z - radius..=z + radius {
+				// Corners are left out at random, and always on the top layer.
+				let corner =
+					(bx - x).abs() == radius && (bz - z).abs() == radius;
+				if (!corner || random.next_int(2) != 0 && above_top != 0)
+					&& !region.is_opaque(bx, by, bz)
+				{
+					region.set_block(bx, by, bz, blocks::LEAVES);
+				}
+			}
+		}
+	}
+
+	for by in y..y + height {
+		if matches!(region.get_block(x, by, z), blocks::AIR | blocks::LEAVES) {
+			region.set_block(x, by, z, blocks::LOG);
+		}
+	}
+}
+
+/// Scatters a patch of `plant` around `(x, y, z)`.
+pub fn plants(
+	region: &mut Region,
+	random: &mut JavaRandom,
+	plant: u8,
+	(x, y, z): (i32, i32, i32),
+) {
+	for _ in 0..64 {
+		let (bx, by, bz) = scatter(random, (x, y, z));
+		if region.get_block(bx, by, bz) == blocks::AIR
+			&& can_plant_stay(region, plant, (bx, by, bz))
+		{
+			region.set_block(bx, by, bz, plant);
+		}
+	}
+}
+
+fn can_plant_stay(
+	region: &Region,
+	plant: u8,
+	(x, y, z): (i32, i32, i32),
+) -> bool {
+	let below = region.get_block(x, y - 1, z);
+	let sky = y >= region.height(x, z);
+
+	match plant {
+		blocks::BROWN_MUSHROOM | blocks::RED_MUSHROOM => {
+			!sky && region.is_opaque(x, y - 1, z)
+		}
+		_ => sky && matches!(below, blocks::GRASS | blocks::DIRT),
+	}
+}
+
+/// Plants a few stacks of reeds on the banks of water around `(x, y, z)`.
+pub fn reeds(
+	region: &mut Region,
+	random: &mut JavaRandom,
+	(x, y, z): (i32, i32, i32),
+) {
+	for _ in 0..20 {
+		let bx = x + random.next_int(4) - random.next_int(4);
+		let bz = z + random.next_int(4) - random.next_int(4);
+		if region.get_block(bx, y, bz) != blocks::AIR
+			|| !beside_water(region, (bx, y - 1, bz))
+		{
+			continue;
+		}
+
+		let bound = random.next_int(3) + 1;
+		let height = 2 + random.next_int(bound);
+		for by in y..y + height {
+			let below = region.get_block(bx, by - 1, bz);
+			let can_stay = below == blocks::REED
+				|| matches!(below, blocks::GRASS | blocks::DIRT | blocks::SAND)
+					&& beside_water(region, (bx, by - 1, bz));
+			if can_stay {
+				region.set_block(bx, by, bz, blocks::REED);
+			}
+		}
+	}
+}
+
+fn beside_water(region: &Region, (x, y, z): (i32, i32, i32)) -> bool {
+	region.is_water(x - 1, y, z)
+		|| region.is_water(x + 1, y, z)
+		|| region.is_water(x, y, z - 1)
+		|| region.is_water(x, y, z + 1)
+}
+
+/// Scatters pumpkins facing random directions over grass.
+pub fn pumpkins(
+	region: &mut Region,
+	random: &mut JavaRandom,
+	(x, y, z): (i32, i32, i32),
+) {
+	for _ in 0..64 {
+		let (bx, by, bz) = scatter(random, (x, y, z));
+		if region.get_block(bx, by, bz) == blocks::AIR
+			&& region.get_block(bx, by - 1, bz) == blocks::GRASS
+		{
+			let facing = random.next_int(4) as u8;
+			region.set_block_and_meta(bx, by, bz, blocks::PUMPKIN, facing);
+		}
+	}
+}
+
+/// Picks a random spot within eight blocks horizontally and four vertically.
+fn scatter(
+	random: &mut JavaRandom,
+	(x, y, z): (i32, i32, i32),
+) -> (i32, i32, i32) {
+	(
+		x + random.next_int(8) - random.next_int(8),
+		y + random.next_int(4) - random.next_int(4),
+		z + random.next_int(8) - random.next_int(8),
+	)
+}
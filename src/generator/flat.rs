@@ -0,0 +1,62 @@
+use super::{parse_blocks, WorldGenerator};
+use crate::{
+	blocks,
+	chunk::{CHUNK_HEIGHT, CHUNK_WIDTH},
+	light, Chunk, InvalidGeneratorSettingsSnafu, Result,
+};
+use alloc::{string::ToString, vec, vec::Vec};
+use snafu::{ensure, OptionExt};
+
+/// Stacks the same layers of blocks everywhere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlatGenerator {
+	/// Block ids from the bottom of the world up.
+	layers: Vec<u8>,
+}
+
+impl Default for FlatGenerator {
+	/// Bedrock, two layers of dirt and grass on top.
+	fn default() -> Self {
+		Self {
+			layers: vec![
+				blocks::BEDROCK,
+				blocks::DIRT,
+				blocks::DIRT,
+				blocks::GRASS,
+			],
+		}
+	}
+}
+
+impl FlatGenerator {
+	/// Parses a layer list such as `7,2x3,2`, from the bottom up.
+	pub fn from_preset(preset: &str) -> Result<Self> {
+		let settings = || InvalidGeneratorSettingsSnafu {
+			settings: preset.to_string(),
+		};
+		let layers = parse_blocks(preset).with_context(settings)?;
+		ensure!(layers.len() <= CHUNK_HEIGHT, settings());
+
+		Ok(Self { layers })
+	}
+
+	pub fn layers(&self) -> &[u8] {
+		&self.layers
+	}
+}
+
+impl WorldGenerator for FlatGenerator {
+	fn generate(&self, x: i32, z: i32) -> Chunk {
+		let mut chunk = Chunk::new(x, z);
+		for bx in 0..CHUNK_WIDTH {
+			for bz in 0..CHUNK_WIDTH {
+				for (y, block) in self.layers.iter().enumerate() {
+					chunk.set_block(bx, y, bz, *block);
+				}
+			}
+		}
+		light::light_chunk(&mut chunk);
+
+		chunk
+	}
+}
@@ -0,0 +1,189 @@
+//! World generation.
+
+use crate::{
+	blocks,
+	chunk::{block_index, CHUNK_HEIGHT},
+	light, Chunk, Result, UnknownLevelTypeSnafu,
+};
+use alloc::{boxed::Box, string::ToString, vec::Vec};
+
+pub mod alpha;
+pub mod checkerboard;
+mod features;
+pub mod flat;
+mod noise;
+pub mod void;
+
+pub use alpha::AlphaGenerator;
+pub use checkerboard::CheckerboardGenerator;
+pub use flat::FlatGenerator;
+pub use void::VoidGenerator;
+
+/// Produces the contents of chunks that have never been generated before.
+pub trait WorldGenerator: Send {
+	/// Generates the chunk at the given chunk coordinates.
+	fn generate(&self, x: i32, z: i32) -> Chunk;
+
+	/// Adds the features of the chunk at `(x, z)` that may spill over into
+	/// its neighbours. `chunks` holds it and the chunks at `(x + 1, z)`,
+	/// `(x, z + 1)` and `(x + 1, z + 1)`; changes that fall outside of them
+	/// are dropped.
+	fn populate(&self, chunks: &mut [&mut Chunk], x: i32, z: i32) {
+		for chunk in chunks.iter_mut() {
+			if chunk.x == x && chunk.z == z {
+				chunk.populated = true;
+			}
+		}
+	}
+
+	/// Whether players of a new world may spawn standing on `block`. The
+	/// spawn search wanders away from the origin until this holds.
+	fn is_spawn_block(&self, _block: u8) -> bool {
+		true
+	}
+}
+
+/// Which generator a world uses, as chosen by the `level-type` and
+/// `generator-settings` options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum GeneratorSettings {
+	#[default]
+	Alpha,
+	Flat(FlatGenerator),
+	Void,
+	Checkerboard(CheckerboardGenerator),
+}
+
+impl GeneratorSettings {
+	/// Parses a level type and its settings. Empty settings select the
+	/// defaults of the level type.
+	pub fn parse(level_type: &str, settings: &str) -> Result<Self> {
+		let settings = settings.trim();
+
+		match level_type.trim().to_ascii_lowercase().as_str() {
+			"default" | "alpha" => Ok(Self::Alpha),
+			"flat" if settings.is_empty() => {
+				Ok(Self::Flat(FlatGenerator::default()))
+			}
+			"flat" => Ok(Self::Flat(FlatGenerator::from_preset(settings)?)),
+			"void" => Ok(Self::Void),
+			"checkerboard" if settings.is_empty() => {
+				Ok(Self::Checkerboard(CheckerboardGenerator::default()))
+			}
+			"checkerboard" => Ok(Self::Checkerboard(
+				CheckerboardGenerator::from_preset(settings)?,
+			)),
+			_ => UnknownLevelTypeSnafu {
+				name: level_type.to_string(),
+			}
+			.fail(),
+		}
+	}
+
+	/// Creates the generator for a world with the given seed.
+	pub fn build(&self, seed: i64) -> Box<dyn WorldGenerator> {
+		match self {
+			Self::Alpha => Box::new(AlphaGenerator::new(seed)),
+			Self::Flat(generator) => Box::new(generator.clone()),
+			Self::Void => Box::new(VoidGenerator),
+			Self::Checkerboard(generator) => Box::new(generator.clone()),
+		}
+	}
+}
+
+/// Parses a comma separated list of block ids, each optionally prefixed by a
+/// repeat count as in `2x3`. Ids that are not known blocks are rejected, and
+/// so are lists longer than a chunk is high, before anything is allocated
+/// for them.
+fn parse_blocks(preset: &str) -> Option<Vec<u8>> {
+	let mut blocks = Vec::new();
+	let mut total: usize = 0;
+	for entry in preset.split(',') {
+		let (count, block): (usize, _) =
+			match entry.trim().split_once(['x', '*']) {
+				Some((count, block)) => (count.trim().parse().ok()?, block),
+				None => (1, entry),
+			};
+		let block: u8 = block.trim().parse().ok()?;
+		if count == 0 || blocks::get(block).is_none() {
+			return None;
+		}
+		total = total
+			.checked_add(count)
+			.filter(|&total| total <= CHUNK_HEIGHT)?;
+		blocks.extend(core::iter::repeat_n(block, count));
+	}
+
+	Some(blocks)
+}
+
+/// Pi as the Alpha code writes it, rounded to a float literal with fewer
+/// digits than `f32` can hold. Using the exact value would move features.
+#[allow(clippy::approx_constant)]
+const PI: f32 = 3.141593;
+
+/// Block access in world coordinates over the chunks a population step may
+/// touch. Reads outside of them see air and writes are dropped.
+struct Region<'a, 'b> {
+	chunks: &'a mut [&'b mut Chunk],
+}
+
+impl Region<'_, '_> {
+	fn locate(&self, x: i32, y: i32, z: i32) -> Option<(usize, usize)> {
+		if !(0..CHUNK_HEIGHT as i32).contains(&y) {
+			return None;
+		}
+		let chunk = self
+			.chunks
+			.iter()
+			.position(|chunk| chunk.x == x >> 4 && chunk.z == z >> 4)?;
+
+		Some((
+			chunk,
+			block_index((x & 15) as usize, y as usize, (z & 15) as usize),
+		))
+	}
+
+	fn get_block(&self, x: i32, y: i32, z: i32) -> u8 {
+		self.locate(x, y, z).map_or(blocks::AIR, |(chunk, index)| {
+			self.chunks[chunk].blocks[index]
+		})
+	}
+
+	fn set_block(&mut self, x: i32, y: i32, z: i32, block: u8) {
+		self.set_block_and_meta(x, y, z, block, 0);
+	}
+
+	fn set_block_and_meta(
+		&mut self,
+		x: i32,
+		y: i32,
+		z: i32,
+		block: u8,
+		meta: u8,
+	) {
+		if let Some((chunk, index)) = self.locate(x, y, z) {
+			self.chunks[chunk].blocks[index] = block;
+			self.chunks[chunk].data.set(index, meta);
+		}
+	}
+
+	fn is_water(&self, x: i32, y: i32, z: i32) -> bool {
+		matches!(
+			self.get_block(x, y, z),
+			blocks::WATER | blocks::FLOWING_WATER
+		)
+	}
+
+	fn is_opaque(&self, x: i32, y: i32, z: i32) -> bool {
+		light::opacity(self.get_block(x, y, z)) == 15
+	}
+
+	/// Returns the height just above the highest block that absorbs light.
+	fn height(&self, x: i32, z: i32) -> i32 {
+		(0..CHUNK_HEIGHT as i32)
+			.rev()
+			.find(|&y| light::opacity(self.get_block(x, y, z)) != 0)
+			.map_or(0, |y| y + 1)
+	}
+}
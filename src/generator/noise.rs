@@ -0,0 +1,198 @@
+use crate::{math::floor, random::JavaRandom};
+use alloc::{boxed::Box, vec, vec::Vec};
+
+/// Improved Perlin noise with a permutation table and origin offset drawn
+/// from a seeded random, as the Alpha generator builds it.
+pub struct PerlinNoise {
+	permutations: Box<[usize; 512]>,
+	origin: (f64, f64, f64),
+}
+
+fn fade(t: f64) -> f64 {
+	t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+	a + t * (b - a)
+}
+
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+	let hash = hash & 15;
+	let u = if hash < 8 { x } else { y };
+	let v = match hash {
+		0..=3 => y,
+		12 | 14 => x,
+		_ => z,
+	};
+
+	(if hash & 1 == 0 { u } else { -u }) + (if hash & 2 == 0 { v } else { -v })
+}
+
+/// Splits a coordinate into its lattice cell, wrapped to the permutation
+/// table, and the offset within the cell before and after fading.
+fn cell(coordinate: f64) -> (usize, f64, f64) {
+	let floor = floor(coordinate);
+	let offset = coordinate - floor as f64;
+
+	((floor & 255) as usize, offset, fade(offset))
+}
+
+impl PerlinNoise {
+	pub fn new(random: &mut JavaRandom) -> Self {
+		let origin = (
+			random.next_double() * 256.0,
+			random.next_double() * 256.0,
+			random.next_double() * 256.0,
+		);
+
+		let mut permutations = Box::new([0; 512]);
+		for (i, permutation) in permutations.iter_mut().take(256).enumerate() {
+			*permutation = i;
+		}
+		for i in 0..256 {
+			let j = random.next_int(256 - i as i32) as usize + i;
+			permutations.swap(i, j);
+			permutations[i + 256] = permutations[i];
+		}
+
+		Self {
+			permutations,
+			origin,
+		}
+	}
+
+	/// Samples the noise at a single point.
+	pub fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+		let p = &self.permutations;
+		let (cx, x, u) = cell(x + self.origin.0);
+		let (cy, y, v) = cell(y + self.origin.1);
+		let (cz, z, w) = cell(z + self.origin.2);
+
+		let a = p[cx] + cy;
+		let aa = p[a] + cz;
+		let ab = p[a + 1] + cz;
+		let b = p[cx + 1] + cy;
+		let ba = p[b] + cz;
+		let bb = p[b + 1] + cz;
+
+		lerp(
+			w,
+			lerp(
+				v,
+				lerp(u, grad(p[aa], x, y, z), grad(p[ba], x - 1.0, y, z)),
+				lerp(
+					u,
+					grad(p[ab], x, y - 1.0, z),
+					grad(p[bb], x - 1.0, y - 1.0, z),
+				),
+			),
+			lerp(
+				v,
+				lerp(
+					u,
+					grad(p[aa + 1], x, y, z - 1.0),
+					grad(p[ba + 1], x - 1.0, y, z - 1.0),
+				),
+				lerp(
+					u,
+					grad(p[ab + 1], x, y - 1.0, z - 1.0),
+					grad(p[bb + 1], x - 1.0, y - 1.0, z - 1.0),
+				),
+			),
+		)
+	}
+
+	/// Adds `amplitude` times the noise over a grid of points to `out`,
+	/// ordered by x, then z, then y, with y varying fastest.
+	fn add_grid(
+		&self,
+		out: &mut [f64],
+		origin: (f64, f64, f64),
+		size: (usize, usize, usize),
+		scale: (f64, f64, f64),
+		amplitude: f64,
+	) {
+		let mut index = 0;
+		for i in 0..size.0 {
+			let x = (origin.0 + i as f64) * scale.0;
+			for k in 0..size.2 {
+				let z = (origin.2 + k as f64) * scale.2;
+				for j in 0..size.1 {
+					let y = (origin.1 + j as f64) * scale.1;
+					out[index] += self.sample(x, y, z) * amplitude;
+					index += 1;
+				}
+			}
+		}
+	}
+}
+
+/// Several octaves of Perlin noise, each at half the frequency and double
+/// the amplitude of the previous one.
+pub struct OctaveNoise {
+	octaves: Vec<PerlinNoise>,
+}
+
+impl OctaveNoise {
+	pub fn new(random: &mut JavaRandom, octaves: usize) -> Self {
+		Self {
+			octaves: (0..octaves).map(|_| PerlinNoise::new(random)).collect(),
+		}
+	}
+
+	/// Samples a two-dimensional slice of the noise at a single point.
+	pub fn sample_2d(&self, x: f64, z: f64) -> f64 {
+		let mut frequency = 1.0;
+		let mut total = 0.0;
+		for octave in &self.octaves {
+			total +=
+				octave.sample(x * frequency, z * frequency, 0.0) / frequency;
+			frequency /= 2.0;
+		}
+
+		total
+	}
+
+	/// Samples the noise over a grid of `size` points starting at `origin`,
+	/// indexed as `(x * size.2 + z) * size.1 + y`.
+	pub fn grid(
+		&self,
+		origin: (f64, f64, f64),
+		size: (usize, usize, usize),
+		scale: (f64, f64, f64),
+	) -> Vec<f64> {
+		let mut out = vec![0.0; size.0 * size.1 * size.2];
+		let mut frequency = 1.0;
+		for octave in &self.octaves {
+			octave.add_grid(
+				&mut out,
+				origin,
+				size,
+				(
+					scale.0 * frequency,
+					scale.1 * frequency,
+					scale.2 * frequency,
+				),
+				1.0 / frequency,
+			);
+			frequency /= 2.0;
+		}
+
+		out
+	}
+
+	/// Samples a horizontal grid at a fixed height of 10, the way the Alpha
+	/// generator samples its two-dimensional noise fields.
+	pub fn grid_2d(
+		&self,
+		origin: (f64, f64),
+		size: (usize, usize),
+		scale: (f64, f64),
+	) -> Vec<f64> {
+		self.grid(
+			(origin.0, 10.0, origin.1),
+			(size.0, 1, size.1),
+			(scale.0, 1.0, scale.1),
+		)
+	}
+}
@@ -0,0 +1,36 @@
+use super::WorldGenerator;
+use crate::{blocks, light, Chunk};
+
+/// The height of the platform players spawn on.
+pub const PLATFORM_Y: usize = 63;
+
+/// How far the platform reaches from the origin in each direction.
+const PLATFORM_RADIUS: i32 = 2;
+
+/// Leaves the world empty apart from a small stone platform at the origin
+/// to spawn on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoidGenerator;
+
+impl WorldGenerator for VoidGenerator {
+	fn generate(&self, x: i32, z: i32) -> Chunk {
+		let mut chunk = Chunk::new(x, z);
+		for bx in -PLATFORM_RADIUS..=PLATFORM_RADIUS {
+			for bz in -PLATFORM_RADIUS..=PLATFORM_RADIUS {
+				if bx >> 4 == x && bz >> 4 == z {
+					let (local_x, local_z) =
+						((bx & 15) as usize, (bz & 15) as usize);
+					chunk.set_block(
+						local_x,
+						PLATFORM_Y,
+						local_z,
+						blocks::STONE,
+					);
+				}
+			}
+		}
+		light::light_chunk(&mut chunk);
+
+		chunk
+	}
+}
@@ -0,0 +1,126 @@
+//! Player health and the ways the world hurts players.
+
+use crate::{math, packets::ClientboundPacket};
+
+pub const MAX_HEALTH: i16 = 20;
+/// How many ticks a player can hold their breath for.
+pub const MAX_AIR: i16 = 300;
+/// How long a player is immune to further damage after being hurt, in ticks.
+const INVULNERABILITY_TICKS: u8 = 10;
+/// How far a player can fall without getting hurt.
+const SAFE_FALL_DISTANCE: f64 = 3.0;
+const DROWNING_DAMAGE: i16 = 2;
+const LAVA_DAMAGE: i16 = 4;
+const VOID_DAMAGE: i16 = 4;
+
+/// What a player is in the middle of, as far as hurting them is concerned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Surroundings {
+	/// Whether water touches the player anywhere, which breaks their fall.
+	pub in_water: bool,
+	/// Whether their eyes are under water, so they cannot breathe.
+	pub head_in_water: bool,
+	pub in_lava: bool,
+	/// Whether they fell out of the bottom of the world.
+	pub in_void: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Health {
+	pub health: i16,
+	/// How many more ticks the player can stay under water before they
+	/// start drowning.
+	pub air: i16,
+	/// How far the player has fallen since they last stood on the ground.
+	pub fall_distance: f64,
+	/// How many more ticks the player is immune to damage for.
+	pub invulnerable_ticks: u8,
+}
+
+impl Default for Health {
+	fn default() -> Self {
+		Self {
+			health: MAX_HEALTH,
+			air: MAX_AIR,
+			fall_distance: 0.0,
+			invulnerable_ticks: 0,
+		}
+	}
+}
+
+impl Health {
+	pub fn is_dead(&self) -> bool {
+		self.health <= 0
+	}
+
+	/// Hurts the player unless they are dead or were hurt too recently.
+	/// Returns whether their health changed.
+	pub fn damage(&mut self, amount: i16) -> bool {
+		if self.is_dead() || self.invulnerable_ticks > 0 || amount <= 0 {
+			return false;
+		}
+		self.health = (self.health - amount).max(0);
+		self.invulnerable_ticks = INVULNERABILITY_TICKS;
+
+		true
+	}
+
+	/// Follows the player falling by `dy` blocks, or landing when they
+	/// report being on the ground, the way the Alpha server does for every
+	/// movement packet. Returns whether landing hurt them.
+	pub fn fall(&mut self, dy: f64, on_ground: bool) -> bool {
+		if !on_ground {
+			if dy < 0.0 {
+				self.fall_distance -= dy;
+			}
+			return false;
+		}
+
+		// Rounded up, so any fall past the safe distance hurts. Clients report
+		// the distance, so it is capped to keep the damage within an `i16`.
+		let distance = self.fall_distance.min(i16::MAX as f64);
+		let damage = -math::floor(SAFE_FALL_DISTANCE - distance);
+		self.fall_distance = 0.0;
+		self.damage(damage.clamp(0, i16::MAX as i32) as i16)
+	}
+
+	/// Advances the player by one tick in the given surroundings. Returns
+	/// whether their health changed.
+	pub fn tick(&mut self, surroundings: Surroundings) -> bool {
+		self.invulnerable_ticks = self.invulnerable_ticks.saturating_sub(1);
+		if self.is_dead() {
+			return false;
+		}
+
+		let mut hurt = false;
+		if surroundings.in_water {
+			self.fall_distance = 0.0;
+		}
+		if surroundings.head_in_water {
+			self.air -= 1;
+			// Out of air, the player loses health every second.
+			if self.air <= -20 {
+				self.air = 0;
+				hurt |= self.damage(DROWNING_DAMAGE);
+			}
+		} else {
+			self.air = MAX_AIR;
+		}
+		if surroundings.in_lava {
+			hurt |= self.damage(LAVA_DAMAGE);
+		}
+		if surroundings.in_void {
+			hurt |= self.damage(VOID_DAMAGE);
+		}
+
+		hurt
+	}
+
+	/// The UPDATE_HEALTH packet that tells the client how healthy it is. The
+	/// client shows the death screen once it drops to 0.
+	pub fn to_packet(&self) -> ClientboundPacket {
+		ClientboundPacket::UpdateHealth {
+			health: self.health,
+		}
+	}
+}
@@ -0,0 +1,329 @@
+//! What a player carries, in the sections the PLAYER_INVENTORY packet
+//! syncs one at a time.
+
+use crate::{
+	items,
+	nbt::{Compound, Tag},
+	packets::ClientboundPacket,
+	InvalidInventorySnafu, ItemStack, Result,
+};
+use alloc::vec::Vec;
+use snafu::ensure;
+
+/// How many of the main slots, from the first, make up the hotbar.
+pub const HOTBAR_SIZE: usize = 9;
+
+/// A part of the inventory, which the protocol sends separately from the
+/// others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+	/// The hotbar followed by the rest of the backpack.
+	Main,
+	/// Helmet, chestplate, leggings and boots.
+	Armor,
+	/// The 2x2 crafting grid.
+	Crafting,
+}
+
+impl Section {
+	pub const ALL: [Self; 3] = [Self::Main, Self::Armor, Self::Crafting];
+
+	/// The id the PLAYER_INVENTORY packet names the section by.
+	pub fn kind(self) -> i32 {
+		match self {
+			Self::Main => -1,
+			Self::Armor => -2,
+			Self::Crafting => -3,
+		}
+	}
+
+	pub fn from_kind(kind: i32) -> Option<Self> {
+		Self::ALL.into_iter().find(|section| section.kind() == kind)
+	}
+
+	/// How many slots the section has.
+	pub fn size(self) -> usize {
+		match self {
+			Self::Main => 36,
+			Self::Armor | Self::Crafting => 4,
+		}
+	}
+
+	/// The number of the section's first slot in player files.
+	fn first_saved_slot(self) -> usize {
+		match self {
+			Self::Main => 0,
+			Self::Armor => 100,
+			Self::Crafting => 80,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inventory {
+	main: [Option<ItemStack>; 36],
+	armor: [Option<ItemStack>; 4],
+	crafting: [Option<ItemStack>; 4],
+}
+
+impl Default for Inventory {
+	fn default() -> Self {
+		Self {
+			main: [None; 36],
+			armor: [None; 4],
+			crafting: [None; 4],
+		}
+	}
+}
+
+impl Inventory {
+	pub fn section(&self, section: Section) -> &[Option<ItemStack>] {
+		match section {
+			Section::Main => &self.main,
+			Section::Armor => &self.armor,
+			Section::Crafting => &self.crafting,
+		}
+	}
+
+	fn section_mut(&mut self, section: Section) -> &mut [Option<ItemStack>] {
+		match section {
+			Section::Main => &mut self.main,
+			Section::Armor => &mut self.armor,
+			Section::Crafting => &mut self.crafting,
+		}
+	}
+
+	pub fn hotbar(&self) -> &[Option<ItemStack>] {
+		&self.main[..HOTBAR_SIZE]
+	}
+
+	/// Whether any hotbar slot holds the item, which a player has to select
+	/// before using it.
+	pub fn in_hotbar(&self, item_id: i16) -> bool {
+		self.hotbar()
+			.iter()
+			.flatten()
+			.any(|stack| stack.id == item_id)
+	}
+
+	/// Removes one of the item from the first hotbar stack holding it,
+	/// returning `false` if there is none.
+	pub fn take_from_hotbar(&mut self, item_id: i16) -> bool {
+		let Some(slot) = self.main[..HOTBAR_SIZE]
+			.iter_mut()
+			.find(|slot| slot.is_some_and(|stack| stack.id == item_id))
+		else {
+			return false;
+		};
+		if let Some(stack) = slot {
+			stack.count -= 1;
+			if stack.count == 0 {
+				*slot = None;
+			}
+		}
+
+		true
+	}
+
+	/// Puts a stack into the main section, topping up stacks of the same
+	/// item to as many as a slot holds before filling empty slots. Returns
+	/// `false`, leaving the inventory as it was, if the whole stack does not
+	/// fit.
+	pub fn add(&mut self, item: ItemStack) -> bool {
+		let max_stack_size = items::max_stack_size(item.id);
+		let room: u32 = self
+			.main
+			.iter()
+			.map(|slot| match slot {
+				None => max_stack_size as u32,
+				Some(stack)
+					if stack.id == item.id && stack.damage == item.damage =>
+				{
+					max_stack_size.saturating_sub(stack.count) as u32
+				}
+				Some(_) => 0,
+			})
+			.sum();
+		if room < item.count as u32 {
+			return false;
+		}
+
+		let mut left = item.count;
+		for stack in self.main.iter_mut().flatten() {
+			if stack.id == item.id && stack.damage == item.damage {
+				let moved =
+					left.min(max_stack_size.saturating_sub(stack.count));
+				stack.count += moved;
+				left -= moved;
+			}
+		}
+		for slot in self.main.iter_mut().filter(|slot| slot.is_none()) {
+			if left == 0 {
+				break;
+			}
+			let moved = left.min(max_stack_size);
+			*slot = Some(ItemStack {
+				count: moved,
+				..item
+			});
+			left -= moved;
+		}
+
+		true
+	}
+
+	/// Takes `count` of an item out of the main section, from the last
+	/// stacks first, and returns it. Only stacks with the same damage as the
+	/// last one holding the item are taken from, since the taken stack keeps
+	/// it. Returns `None`, leaving the inventory as it was, if there are not
+	/// that many.
+	pub fn remove(&mut self, item_id: i16, count: u8) -> Option<ItemStack> {
+		let damage = self
+			.main
+			.iter()
+			.rev()
+			.flatten()
+			.find(|stack| stack.id == item_id)?
+			.damage;
+		let matches =
+			|stack: &ItemStack| stack.id == item_id && stack.damage == damage;
+		let held: u32 = self
+			.main
+			.iter()
+			.flatten()
+			.filter(|stack| matches(stack))
+			.map(|stack| stack.count as u32)
+			.sum();
+		if held < count as u32 {
+			return None;
+		}
+
+		let mut left = count;
+		for slot in self.main.iter_mut().rev() {
+			let Some(stack) = slot.as_mut().filter(|stack| matches(stack))
+			else {
+				continue;
+			};
+			let moved = left.min(stack.count);
+			stack.count -= moved;
+			left -= moved;
+			if stack.count == 0 {
+				*slot = None;
+			}
+		}
+
+		Some(ItemStack {
+			id: item_id,
+			count,
+			damage,
+		})
+	}
+
+	/// Empties every section, returning what was in them.
+	pub fn take_all(&mut self) -> Vec<ItemStack> {
+		let mut items = Vec::new();
+		for section in Section::ALL {
+			let slots = self.section_mut(section).iter_mut();
+			items.extend(slots.filter_map(Option::take));
+		}
+
+		items
+	}
+
+	/// Replaces a section with the contents a client sent for it. Fails,
+	/// leaving the inventory as it was, if the section is unknown, the
+	/// number of slots is wrong, or a stack holds an unknown item or more of
+	/// it than fits in a slot.
+	pub fn replace(
+		&mut self,
+		kind: i32,
+		items: &[Option<ItemStack>],
+	) -> Result<()> {
+		let section = Section::from_kind(kind);
+		let valid = section.is_some_and(|section| {
+			items.len() == section.size()
+				&& items.iter().flatten().all(|stack| {
+					items::exists(stack.id)
+						&& (1..=items::max_stack_size(stack.id))
+							.contains(&stack.count)
+						&& stack.damage >= 0
+				})
+		});
+		ensure!(valid, InvalidInventorySnafu { kind });
+
+		if let Some(section) = section {
+			self.section_mut(section).copy_from_slice(items);
+		}
+
+		Ok(())
+	}
+
+	/// Converts the inventory to the `Inventory` list of a player file,
+	/// with a compound for each stack naming the slot it is in.
+	pub fn to_nbt(&self) -> Tag {
+		let mut stacks = Vec::new();
+		for section in Section::ALL {
+			let slots = self.section(section).iter().enumerate();
+			for (i, stack) in slots {
+				let Some(stack) = stack else {
+					continue;
+				};
+				let slot = section.first_saved_slot() + i;
+				stacks.push(Tag::Compound(
+					Compound::new()
+						.with("Slot", Tag::Byte(slot as i8))
+						.with("id", Tag::Short(stack.id))
+						.with("Count", Tag::Byte(stack.count as i8))
+						.with("Damage", Tag::Short(stack.damage)),
+				));
+			}
+		}
+
+		Tag::list(stacks)
+	}
+
+	/// Reads an inventory from the `Inventory` list of a player file.
+	/// Stacks in slots that do not exist are left out, as the Alpha server
+	/// does, and so are stacks of unknown items.
+	pub fn from_nbt(tag: &Tag) -> Self {
+		let mut inventory = Self::default();
+		let stacks = tag.as_list().unwrap_or_default();
+		for stack in stacks.iter().filter_map(Tag::as_compound) {
+			let field = |name| stack.get(name);
+			let (Some(slot), Some(id), Some(count)) = (
+				field("Slot").and_then(Tag::as_byte),
+				field("id").and_then(Tag::as_short),
+				field("Count").and_then(Tag::as_byte),
+			) else {
+				continue;
+			};
+			if !items::exists(id) || count <= 0 {
+				continue;
+			}
+			let damage = field("Damage").and_then(Tag::as_short).unwrap_or(0);
+
+			let slot = slot as u8 as usize;
+			let place = Section::ALL.into_iter().find_map(|section| {
+				let i = slot.checked_sub(section.first_saved_slot())?;
+				(i < section.size()).then_some((section, i))
+			});
+			if let Some((section, i)) = place {
+				inventory.section_mut(section)[i] = Some(ItemStack {
+					id,
+					count: count as u8,
+					damage,
+				});
+			}
+		}
+
+		inventory
+	}
+
+	/// The PLAYER_INVENTORY packet that sets a section on the client.
+	pub fn to_packet(&self, section: Section) -> ClientboundPacket {
+		ClientboundPacket::PlayerInventory {
+			kind: section.kind(),
+			items: Vec::from(self.section(section)),
+		}
+	}
+}
@@ -0,0 +1,319 @@
+//! Item ids as they appear on the wire, and what the Alpha client knows
+//! about each item. Ids below 256 are blocks, which [`crate::blocks`]
+//! describes.
+
+use crate::blocks;
+
+pub const IRON_SHOVEL: i16 = 256;
+pub const IRON_PICKAXE: i16 = 257;
+pub const IRON_AXE: i16 = 258;
+pub const FLINT_AND_STEEL: i16 = 259;
+pub const APPLE: i16 = 260;
+pub const BOW: i16 = 261;
+pub const ARROW: i16 = 262;
+pub const COAL: i16 = 263;
+pub const DIAMOND: i16 = 264;
+pub const IRON_INGOT: i16 = 265;
+pub const GOLD_INGOT: i16 = 266;
+pub const IRON_SWORD: i16 = 267;
+pub const WOODEN_SWORD: i16 = 268;
+pub const WOODEN_SHOVEL: i16 = 269;
+pub const WOODEN_PICKAXE: i16 = 270;
+pub const WOODEN_AXE: i16 = 271;
+pub const STONE_SWORD: i16 = 272;
+pub const STONE_SHOVEL: i16 = 273;
+pub const STONE_PICKAXE: i16 = 274;
+pub const STONE_AXE: i16 = 275;
+pub const DIAMOND_SWORD: i16 = 276;
+pub const DIAMOND_SHOVEL: i16 = 277;
+pub const DIAMOND_PICKAXE: i16 = 278;
+pub const DIAMOND_AXE: i16 = 279;
+pub const STICK: i16 = 280;
+pub const BOWL: i16 = 281;
+pub const MUSHROOM_SOUP: i16 = 282;
+pub const GOLDEN_SWORD: i16 = 283;
+pub const GOLDEN_SHOVEL: i16 = 284;
+pub const GOLDEN_PICKAXE: i16 = 285;
+pub const GOLDEN_AXE: i16 = 286;
+pub const STRING: i16 = 287;
+pub const FEATHER: i16 = 288;
+pub const GUNPOWDER: i16 = 289;
+pub const WOODEN_HOE: i16 = 290;
+pub const STONE_HOE: i16 = 291;
+pub const IRON_HOE: i16 = 292;
+pub const DIAMOND_HOE: i16 = 293;
+pub const GOLDEN_HOE: i16 = 294;
+pub const SEEDS: i16 = 295;
+pub const WHEAT: i16 = 296;
+pub const BREAD: i16 = 297;
+pub const LEATHER_HELMET: i16 = 298;
+pub const LEATHER_CHESTPLATE: i16 = 299;
+pub const LEATHER_LEGGINGS: i16 = 300;
+pub const LEATHER_BOOTS: i16 = 301;
+pub const CHAIN_HELMET: i16 = 302;
+pub const CHAIN_CHESTPLATE: i16 = 303;
+pub const CHAIN_LEGGINGS: i16 = 304;
+pub const CHAIN_BOOTS: i16 = 305;
+pub const IRON_HELMET: i16 = 306;
+pub const IRON_CHESTPLATE: i16 = 307;
+pub const IRON_LEGGINGS: i16 = 308;
+pub const IRON_BOOTS: i16 = 309;
+pub const DIAMOND_HELMET: i16 = 310;
+pub const DIAMOND_CHESTPLATE: i16 = 311;
+pub const DIAMOND_LEGGINGS: i16 = 312;
+pub const DIAMOND_BOOTS: i16 = 313;
+pub const GOLDEN_HELMET: i16 = 314;
+pub const GOLDEN_CHESTPLATE: i16 = 315;
+pub const GOLDEN_LEGGINGS: i16 = 316;
+pub const GOLDEN_BOOTS: i16 = 317;
+pub const FLINT: i16 = 318;
+pub const RAW_PORKCHOP: i16 = 319;
+pub const COOKED_PORKCHOP: i16 = 320;
+pub const PAINTING: i16 = 321;
+pub const GOLDEN_APPLE: i16 = 322;
+pub const SIGN: i16 = 323;
+pub const WOODEN_DOOR: i16 = 324;
+pub const BUCKET: i16 = 325;
+pub const WATER_BUCKET: i16 = 326;
+pub const LAVA_BUCKET: i16 = 327;
+pub const MINECART: i16 = 328;
+pub const SADDLE: i16 = 329;
+pub const IRON_DOOR: i16 = 330;
+pub const REDSTONE: i16 = 331;
+pub const SNOWBALL: i16 = 332;
+pub const BOAT: i16 = 333;
+pub const LEATHER: i16 = 334;
+pub const MILK_BUCKET: i16 = 335;
+pub const BRICK: i16 = 336;
+pub const CLAY_BALL: i16 = 337;
+pub const REEDS: i16 = 338;
+pub const PAPER: i16 = 339;
+pub const BOOK: i16 = 340;
+pub const SLIMEBALL: i16 = 341;
+pub const STORAGE_MINECART: i16 = 342;
+pub const POWERED_MINECART: i16 = 343;
+pub const EGG: i16 = 344;
+pub const COMPASS: i16 = 345;
+pub const FISHING_ROD: i16 = 346;
+pub const CLOCK: i16 = 347;
+pub const GLOWSTONE_DUST: i16 = 348;
+pub const RAW_FISH: i16 = 349;
+pub const COOKED_FISH: i16 = 350;
+pub const GOLD_RECORD: i16 = 2256;
+pub const GREEN_RECORD: i16 = 2257;
+
+/// What a tool is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolKind {
+	Pickaxe,
+	Shovel,
+	Axe,
+	Sword,
+	Hoe,
+}
+
+/// A kind of tool and how good it is: 0 for wood and gold, 1 for stone, 2
+/// for iron and 3 for diamond.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tool {
+	pub kind: ToolKind,
+	pub tier: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Item {
+	pub id: i16,
+	pub name: &'static str,
+	/// The most of the item a single slot holds.
+	pub max_stack_size: u8,
+	/// How many uses the item survives, or 0 if it does not wear out.
+	pub max_damage: u16,
+	pub tool: Option<Tool>,
+}
+
+impl Item {
+	const fn new(id: i16, name: &'static str) -> Self {
+		Self {
+			id,
+			name,
+			max_stack_size: 64,
+			max_damage: 0,
+			tool: None,
+		}
+	}
+
+	/// An item that does not stack.
+	const fn single(id: i16, name: &'static str) -> Self {
+		Self {
+			max_stack_size: 1,
+			..Self::new(id, name)
+		}
+	}
+
+	const fn stacks_to(self, max_stack_size: u8) -> Self {
+		Self {
+			max_stack_size,
+			..self
+		}
+	}
+
+	const fn wears_out(self, max_damage: u16) -> Self {
+		Self { max_damage, ..self }
+	}
+
+	/// A tool of the given tier, which wears out after `32 << tier` uses
+	/// like every Alpha tool. Gold counts as the lowest tier.
+	const fn tool(
+		id: i16,
+		name: &'static str,
+		kind: ToolKind,
+		tier: u8,
+	) -> Self {
+		Self {
+			tool: Some(Tool { kind, tier }),
+			..Self::single(id, name).wears_out(32 << tier)
+		}
+	}
+
+	/// A piece of armor. `slot` is 0 for helmets through 3 for boots, and
+	/// `material` 0 for leather, 1 for chain and gold, 2 for iron and 3 for
+	/// diamond.
+	const fn armor(
+		id: i16,
+		name: &'static str,
+		slot: usize,
+		material: u8,
+	) -> Self {
+		const DURABILITY: [u16; 4] = [11, 16, 15, 13];
+		Self::single(id, name).wears_out((DURABILITY[slot] * 3) << material)
+	}
+}
+
+/// Every item that is not a block, ordered by id.
+static ITEMS: &[Item] = &[
+	Item::tool(IRON_SHOVEL, "Iron Shovel", ToolKind::Shovel, 2),
+	Item::tool(IRON_PICKAXE, "Iron Pickaxe", ToolKind::Pickaxe, 2),
+	Item::tool(IRON_AXE, "Iron Axe", ToolKind::Axe, 2),
+	Item::single(FLINT_AND_STEEL, "Flint and Steel").wears_out(64),
+	Item::single(APPLE, "Apple"),
+	Item::single(BOW, "Bow"),
+	Item::new(ARROW, "Arrow"),
+	Item::new(COAL, "Coal"),
+	Item::new(DIAMOND, "Diamond"),
+	Item::new(IRON_INGOT, "Iron Ingot"),
+	Item::new(GOLD_INGOT, "Gold Ingot"),
+	Item::tool(IRON_SWORD, "Iron Sword", ToolKind::Sword, 2),
+	Item::tool(WOODEN_SWORD, "Wooden Sword", ToolKind::Sword, 0),
+	Item::tool(WOODEN_SHOVEL, "Wooden Shovel", ToolKind::Shovel, 0),
+	Item::tool(WOODEN_PICKAXE, "Wooden Pickaxe", ToolKind::Pickaxe, 0),
+	Item::tool(WOODEN_AXE, "Wooden Axe", ToolKind::Axe, 0),
+	Item::tool(STONE_SWORD, "Stone Sword", ToolKind::Sword, 1),
+	Item::tool(STONE_SHOVEL, "Stone Shovel", ToolKind::Shovel, 1),
+	Item::tool(STONE_PICKAXE, "Stone Pickaxe", ToolKind::Pickaxe, 1),
+	Item::tool(STONE_AXE, "Stone Axe", ToolKind::Axe, 1),
+	Item::tool(DIAMOND_SWORD, "Diamond Sword", ToolKind::Sword, 3),
+	Item::tool(DIAMOND_SHOVEL, "Diamond Shovel", ToolKind::Shovel, 3),
+	Item::tool(DIAMOND_PICKAXE, "Diamond Pickaxe", ToolKind::Pickaxe, 3),
+	Item::tool(DIAMOND_AXE, "Diamond Axe", ToolKind::Axe, 3),
+	Item::new(STICK, "Stick"),
+	Item::new(BOWL, "Bowl"),
+	Item::single(MUSHROOM_SOUP, "Mushroom Stew"),
+	Item::tool(GOLDEN_SWORD, "Golden Sword", ToolKind::Sword, 0),
+	Item::tool(GOLDEN_SHOVEL, "Golden Shovel", ToolKind::Shovel, 0),
+	Item::tool(GOLDEN_PICKAXE, "Golden Pickaxe", ToolKind::Pickaxe, 0),
+	Item::tool(GOLDEN_AXE, "Golden Axe", ToolKind::Axe, 0),
+	Item::new(STRING, "String"),
+	Item::new(FEATHER, "Feather"),
+	Item::new(GUNPOWDER, "Gunpowder"),
+	Item::tool(WOODEN_HOE, "Wooden Hoe", ToolKind::Hoe, 0),
+	Item::tool(STONE_HOE, "Stone Hoe", ToolKind::Hoe, 1),
+	Item::tool(IRON_HOE, "Iron Hoe", ToolKind::Hoe, 2),
+	Item::tool(DIAMOND_HOE, "Diamond Hoe", ToolKind::Hoe, 3),
+	Item::tool(GOLDEN_HOE, "Golden Hoe", ToolKind::Hoe, 0),
+	Item::new(SEEDS, "Seeds"),
+	Item::new(WHEAT, "Wheat"),
+	Item::single(BREAD, "Bread"),
+	Item::armor(LEATHER_HELMET, "Leather Cap", 0, 0),
+	Item::armor(LEATHER_CHESTPLATE, "Leather Tunic", 1, 0),
+	Item::armor(LEATHER_LEGGINGS, "Leather Pants", 2, 0),
+	Item::armor(LEATHER_BOOTS, "Leather Boots", 3, 0),
+	Item::armor(CHAIN_HELMET, "Chain Helmet", 0, 1),
+	Item::armor(CHAIN_CHESTPLATE, "Chain Chestplate", 1, 1),
+	Item::armor(CHAIN_LEGGINGS, "Chain Leggings", 2, 1),
+	Item::armor(CHAIN_BOOTS, "Chain Boots", 3, 1),
+	Item::armor(IRON_HELMET, "Iron Helmet", 0, 2),
+	Item::armor(IRON_CHESTPLATE, "Iron Chestplate", 1, 2),
+	Item::armor(IRON_LEGGINGS, "Iron Leggings", 2, 2),
+	Item::armor(IRON_BOOTS, "Iron Boots", 3, 2),
+	Item::armor(DIAMOND_HELMET, "Diamond Helmet", 0, 3),
+	Item::armor(DIAMOND_CHESTPLATE, "Diamond Chestplate", 1, 3),
+	Item::armor(DIAMOND_LEGGINGS, "Diamond Leggings", 2, 3),
+	Item::armor(DIAMOND_BOOTS, "Diamond Boots", 3, 3),
+	Item::armor(GOLDEN_HELMET, "Golden Helmet", 0, 1),
+	Item::armor(GOLDEN_CHESTPLATE, "Golden Chestplate", 1, 1),
+	Item::armor(GOLDEN_LEGGINGS, "Golden Leggings", 2, 1),
+	Item::armor(GOLDEN_BOOTS, "Golden Boots", 3, 1),
+	Item::new(FLINT, "Flint"),
+	Item::single(RAW_PORKCHOP, "Raw Porkchop"),
+	Item::single(COOKED_PORKCHOP, "Cooked Porkchop"),
+	Item::new(PAINTING, "Painting"),
+	Item::single(GOLDEN_APPLE, "Golden Apple"),
+	Item::single(SIGN, "Sign"),
+	Item::single(WOODEN_DOOR, "Wooden Door"),
+	Item::single(BUCKET, "Bucket"),
+	Item::single(WATER_BUCKET, "Water Bucket"),
+	Item::single(LAVA_BUCKET, "Lava Bucket"),
+	Item::single(MINECART, "Minecart"),
+	Item::single(SADDLE, "Saddle"),
+	Item::single(IRON_DOOR, "Iron Door"),
+	Item::new(REDSTONE, "Redstone"),
+	Item::new(SNOWBALL, "Snowball").stacks_to(16),
+	Item::single(BOAT, "Boat"),
+	Item::new(LEATHER, "Leather"),
+	Item::single(MILK_BUCKET, "Milk Bucket"),
+	Item::new(BRICK, "Brick"),
+	Item::new(CLAY_BALL, "Clay"),
+	Item::new(REEDS, "Reeds"),
+	Item::new(PAPER, "Paper"),
+	Item::new(BOOK, "Book"),
+	Item::new(SLIMEBALL, "Slimeball"),
+	Item::single(STORAGE_MINECART, "Minecart with Chest"),
+	Item::single(POWERED_MINECART, "Minecart with Furnace"),
+	Item::new(EGG, "Egg").stacks_to(16),
+	Item::new(COMPASS, "Compass"),
+	Item::single(FISHING_ROD, "Fishing Rod").wears_out(64),
+	Item::new(CLOCK, "Clock"),
+	Item::new(GLOWSTONE_DUST, "Glowstone Dust"),
+	Item::new(RAW_FISH, "Raw Fish"),
+	Item::new(COOKED_FISH, "Cooked Fish"),
+	Item::single(GOLD_RECORD, "Gold Record"),
+	Item::single(GREEN_RECORD, "Green Record"),
+];
+
+/// Looks up an item that is not a block.
+pub fn get(id: i16) -> Option<&'static Item> {
+	ITEMS
+		.binary_search_by_key(&id, |item| item.id)
+		.ok()
+		.map(|index| &ITEMS[index])
+}
+
+/// Whether the id names a block or an item the client knows.
+pub fn exists(id: i16) -> bool {
+	match u8::try_from(id) {
+		Ok(block) => {
+			blocks::get(block).is_some_and(|block| block.id != blocks::AIR)
+		}
+		Err(_) => get(id).is_some(),
+	}
+}
+
+/// The most of an item a single slot holds, which is 64 for every block,
+/// or 0 if there is no such item.
+pub fn max_stack_size(id: i16) -> u8 {
+	match u8::try_from(id) {
+		Ok(_) if exists(id) => 64,
+		Ok(_) => 0,
+		Err(_) => get(id).map_or(0, |item| item.max_stack_size),
+	}
+}
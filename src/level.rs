@@ -0,0 +1,64 @@
+use crate::{
+	nbt::{Compound, Tag},
+	MissingFieldSnafu, Result,
+};
+use snafu::OptionExt;
+
+/// The contents of a world's `level.dat`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LevelData {
+	pub seed: i64,
+	pub spawn_x: i32,
+	pub spawn_y: i32,
+	pub spawn_z: i32,
+	pub time: i64,
+	/// Milliseconds since the Unix epoch.
+	pub last_played: i64,
+	pub size_on_disk: i64,
+}
+
+impl LevelData {
+	/// Converts the level data to the root compound of `level.dat`.
+	pub fn to_nbt(&self) -> Tag {
+		let data = Compound::new()
+			.with("RandomSeed", Tag::Long(self.seed))
+			.with("SpawnX", Tag::Int(self.spawn_x))
+			.with("SpawnY", Tag::Int(self.spawn_y))
+			.with("SpawnZ", Tag::Int(self.spawn_z))
+			.with("Time", Tag::Long(self.time))
+			.with("LastPlayed", Tag::Long(self.last_played))
+			.with("SizeOnDisk", Tag::Long(self.size_on_disk));
+
+		Tag::Compound(Compound::new().with("Data", Tag::Compound(data)))
+	}
+
+	/// Reads level data from the root compound of `level.dat`.
+	pub fn from_nbt(tag: &Tag) -> Result<Self> {
+		let data = tag
+			.as_compound()
+			.and_then(|root| root.get("Data"))
+			.and_then(Tag::as_compound)
+			.context(MissingFieldSnafu { name: "Data" })?;
+
+		let int = |name: &'static str| {
+			data.get(name)
+				.and_then(Tag::as_int)
+				.context(MissingFieldSnafu { name })
+		};
+		let long = |name: &'static str| {
+			data.get(name)
+				.and_then(Tag::as_long)
+				.context(MissingFieldSnafu { name })
+		};
+
+		Ok(Self {
+			seed: long("RandomSeed")?,
+			spawn_x: int("SpawnX")?,
+			spawn_y: int("SpawnY")?,
+			spawn_z: int("SpawnZ")?,
+			time: long("Time")?,
+			last_played: long("LastPlayed").unwrap_or_default(),
+			size_on_disk: long("SizeOnDisk").unwrap_or_default(),
+		})
+	}
+}
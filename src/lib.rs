@@ -2,7 +2,77 @@
 
 extern crate alloc;
 
-use alloc::{string::String, vec::Vec};
+use alloc::{
+	string::{String, ToString},
+	vec,
+};
+use nbt::{Compound, Tag};
+use snafu::{OptionExt, Snafu};
+
+pub mod blocks;
+pub mod bytes;
+pub mod chunk;
+pub mod entity;
+pub mod generator;
+pub mod health;
+pub mod inventory;
+pub mod items;
+pub mod level;
+pub mod light;
+pub mod math;
+pub mod nbt;
+pub mod packets;
+pub mod random;
+
+pub use chunk::Chunk;
+pub use health::Health;
+pub use inventory::Inventory;
+
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum Error {
+	#[snafu(display("{message}"))]
+	BitsError {
+		message: String,
+	},
+	#[snafu(display("unknown packet {id:#04x}"))]
+	UnknownPacket {
+		id: u8,
+	},
+	InvalidTag {
+		id: u8,
+	},
+	InvalidGzip,
+	InvalidZlib,
+	TagTooDeep,
+	MissingField {
+		name: &'static str,
+	},
+	UnknownLevelType {
+		name: String,
+	},
+	InvalidGeneratorSettings {
+		settings: String,
+	},
+	#[snafu(display("invalid length {length}"))]
+	InvalidLength {
+		length: i64,
+	},
+	#[snafu(display("invalid contents for inventory section {kind}"))]
+	InvalidInventory {
+		kind: i32,
+	},
+}
+
+impl From<irox_bits::Error> for Error {
+	fn from(value: irox_bits::Error) -> Self {
+		Self::BitsError {
+			message: value.to_string(),
+		}
+	}
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Player {
@@ -15,56 +85,110 @@ pub struct Player {
 	pub pitch: f32,
 	pub stance: f64,
 	pub on_ground: bool,
+	pub inventory: Inventory,
+	/// The id of the item in the player's hand, or 0 for an empty hand.
+	pub held_item: i16,
+	pub health: Health,
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
-pub struct Chunk {
-	pub x: i32,
-	pub z: i32,
-	pub blocks: Vec<u8>,
-	pub data: Vec<u8>,
-	pub sky_light: Vec<u8>,
-	pub block_light: Vec<u8>,
+impl Player {
+	/// Returns the coordinates of the chunk the player is standing in.
+	pub fn chunk_position(&self) -> (i32, i32) {
+		(math::floor(self.x) >> 4, math::floor(self.z) >> 4)
+	}
+
+	/// Converts what the player file keeps about the player to its root
+	/// compound, laid out the way the Alpha server writes it.
+	pub fn to_nbt(&self) -> Tag {
+		let doubles = |values: [f64; 3]| {
+			Tag::list(values.into_iter().map(Tag::Double).collect())
+		};
+
+		Tag::Compound(
+			Compound::new()
+				.with("Pos", doubles([self.x, self.y, self.z]))
+				.with("Motion", doubles([0.0; 3]))
+				.with(
+					"Rotation",
+					Tag::list(vec![
+						Tag::Float(self.yaw),
+						Tag::Float(self.pitch),
+					]),
+				)
+				.with(
+					"FallDistance",
+					Tag::Float(self.health.fall_distance as f32),
+				)
+				.with("Fire", Tag::Short(0))
+				.with("Air", Tag::Short(self.health.air))
+				.with("OnGround", Tag::Byte(self.on_ground as i8))
+				.with("Health", Tag::Short(self.health.health))
+				.with("Inventory", self.inventory.to_nbt())
+				.with("Dimension", Tag::Int(0)),
+		)
+	}
+
+	/// Restores what [`Player::to_nbt`] saved. Fields the file lacks keep
+	/// the values the player already has.
+	pub fn load_nbt(&mut self, tag: &Tag) -> Result<()> {
+		let root = tag
+			.as_compound()
+			.context(MissingFieldSnafu { name: "Player" })?;
+
+		let list = |name| root.get(name).and_then(Tag::as_list);
+		if let Some([x, y, z]) = list("Pos") {
+			if let (Some(x), Some(y), Some(z)) =
+				(x.as_double(), y.as_double(), z.as_double())
+			{
+				(self.x, self.y, self.z) = (x, y, z);
+				self.stance = y + 1.62;
+			}
+		}
+		if let Some([yaw, pitch]) = list("Rotation") {
+			if let (Some(yaw), Some(pitch)) = (yaw.as_float(), pitch.as_float())
+			{
+				(self.yaw, self.pitch) = (yaw, pitch);
+			}
+		}
+		if let Some(distance) = root.get("FallDistance").and_then(Tag::as_float)
+		{
+			self.health.fall_distance = distance as f64;
+		}
+		if let Some(air) = root.get("Air").and_then(Tag::as_short) {
+			self.health.air = air;
+		}
+		if let Some(on_ground) = root.get("OnGround").and_then(Tag::as_byte) {
+			self.on_ground = on_ground != 0;
+		}
+		if let Some(health) = root.get("Health").and_then(Tag::as_short) {
+			self.health.health = health;
+		}
+		if let Some(inventory) = root.get("Inventory") {
+			self.inventory = Inventory::from_nbt(inventory);
+		}
+
+		Ok(())
+	}
+
+	/// Whether the player's bounding box overlaps the block at the given
+	/// coordinates.
+	pub fn intersects_block(&self, x: i32, y: i32, z: i32) -> bool {
+		const HALF_WIDTH: f64 = 0.3;
+		const HEIGHT: f64 = 1.8;
+
+		let (x, y, z) = (x as f64, y as f64, z as f64);
+		self.x + HALF_WIDTH > x
+			&& self.x - HALF_WIDTH < x + 1.0
+			&& self.y + HEIGHT > y
+			&& self.y < y + 1.0
+			&& self.z + HALF_WIDTH > z
+			&& self.z - HALF_WIDTH < z + 1.0
+	}
 }
 
-pub mod packets {
-	pub const KEEP_ALIVE: u8 = 0x00;
-	pub const LOGIN: u8 = 0x01;
-	pub const HANDSHAKE: u8 = 0x02;
-	pub const CHAT_MESSAGE: u8 = 0x03;
-	pub const TIME_UPDATE: u8 = 0x04;
-	pub const PLAYER_INVENTORY: u8 = 0x05;
-	pub const SPAWN_POSITION: u8 = 0x06;
-	pub const USE_ENTITY: u8 = 0x07;
-	pub const UPDATE_HEALTH: u8 = 0x08;
-	pub const RESPAWN: u8 = 0x09;
-	pub const PLAYER: u8 = 0x0A;
-	pub const PLAYER_POSITION: u8 = 0x0B;
-	pub const PLAYER_LOOK: u8 = 0x0C;
-	pub const PLAYER_POSITION_AND_LOOK: u8 = 0x0D;
-	pub const PLAYER_DIGGING: u8 = 0x0E;
-	pub const PLAYER_BLOCK_PLACEMENT: u8 = 0x0F;
-	pub const HOLDING_CHANGE: u8 = 0x10;
-	pub const ADD_TO_INVENTORY: u8 = 0x11;
-	pub const ANIMATION: u8 = 0x12;
-	pub const NAMED_ENTITY_SPAWN: u8 = 0x14;
-	pub const PICKUP_SPAWN: u8 = 0x15;
-	pub const COLLECT_ITEM: u8 = 0x16;
-	pub const ADD_OBJECT_OR_VEHICLE: u8 = 0x17;
-	pub const MOB_SPAWN: u8 = 0x18;
-	pub const ENTITY_VELOCITY: u8 = 0x1C;
-	pub const DESTROY_ENTITY: u8 = 0x1D;
-	pub const ENTITY: u8 = 0x1E;
-	pub const ENTITY_RELATIVE_MOVE: u8 = 0x1F;
-	pub const ENTITY_LOOK: u8 = 0x20;
-	pub const ENTITY_LOOK_AND_RELATIVE_MOVE: u8 = 0x21;
-	pub const ENTITY_TELEPORT: u8 = 0x22;
-	pub const ENTITY_STATUS: u8 = 0x26;
-	pub const PRE_CHUNK: u8 = 0x32;
-	pub const MAP_CHUNK: u8 = 0x33;
-	pub const MULTI_BLOCK_CHANGE: u8 = 0x34;
-	pub const BLOCK_CHANGE: u8 = 0x35;
-	pub const COMPLEX_ENTITY: u8 = 0x3B;
-	pub const EXPLOSION: u8 = 0x3C;
-	pub const KICK_OR_DISCONNECT: u8 = 0xFF;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemStack {
+	pub id: i16,
+	pub count: u8,
+	pub damage: i16,
 }
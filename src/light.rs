@@ -0,0 +1,352 @@
+//! Sky and block light. Sky light comes down every column, dimmed only by
+//! the blocks it passes through, block light comes from emitters such as
+//! torches, and both spread to neighbouring blocks, losing at least one
+//! level per block.
+
+use crate::{
+	blocks,
+	chunk::{block_index, CHUNK_HEIGHT, CHUNK_WIDTH},
+	Chunk,
+};
+use alloc::{
+	collections::{BTreeMap, VecDeque},
+	vec,
+	vec::Vec,
+};
+
+/// The brightest light there is, which sky light has wherever the sky is
+/// visible.
+pub const MAX_LIGHT: u8 = 15;
+
+const NEIGHBOURS: [(i32, i32, i32); 6] = [
+	(-1, 0, 0),
+	(1, 0, 0),
+	(0, -1, 0),
+	(0, 1, 0),
+	(0, 0, -1),
+	(0, 0, 1),
+];
+
+/// The sky light coming down each column, by the column's world
+/// coordinates, kept so that it is only worked out once per column.
+type SkyColumns = BTreeMap<(i32, i32), [u8; CHUNK_HEIGHT]>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightKind {
+	Sky,
+	Block,
+}
+
+impl LightKind {
+	const ALL: [Self; 2] = [Self::Sky, Self::Block];
+}
+
+/// How much light passing through a block is dimmed by. Unknown blocks
+/// stop it entirely.
+pub fn opacity(block: u8) -> u8 {
+	blocks::get(block).map_or(MAX_LIGHT, |block| block.light_opacity)
+}
+
+/// How much light a block gives off by itself.
+pub fn emission(block: u8) -> u8 {
+	blocks::get(block).map_or(0, |block| block.light_emission)
+}
+
+/// Lights a chunk on its own, as if there was nothing around it.
+pub fn light_chunk(chunk: &mut Chunk) {
+	let (x, z) = (chunk.x, chunk.z);
+	Lighting::new(&mut [chunk]).light_chunk(x, z);
+}
+
+/// Light access in world coordinates over a chunk and whichever of its
+/// neighbours are loaded. Light does not reach into chunks that are not
+/// there, and nothing spreads further than the chunks next to where it
+/// started, so this is all an update needs.
+pub struct Lighting<'a, 'b> {
+	chunks: &'a mut [&'b mut Chunk],
+	/// Which of the chunks had their light changed.
+	changed: Vec<bool>,
+}
+
+impl<'a, 'b> Lighting<'a, 'b> {
+	pub fn new(chunks: &'a mut [&'b mut Chunk]) -> Self {
+		let changed = vec![false; chunks.len()];
+		Self { chunks, changed }
+	}
+
+	/// The coordinates of the chunks whose light changed.
+	pub fn changed_chunks(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+		self.chunks
+			.iter()
+			.zip(&self.changed)
+			.filter(|(_, changed)| **changed)
+			.map(|(chunk, _)| (chunk.x, chunk.z))
+	}
+
+	fn locate(&self, x: i32, y: i32, z: i32) -> Option<(usize, usize)> {
+		if !(0..CHUNK_HEIGHT as i32).contains(&y) {
+			return None;
+		}
+		let chunk = self
+			.chunks
+			.iter()
+			.position(|chunk| chunk.x == x >> 4 && chunk.z == z >> 4)?;
+
+		Some((
+			chunk,
+			block_index((x & 15) as usize, y as usize, (z & 15) as usize),
+		))
+	}
+
+	fn block(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+		let (chunk, index) = self.locate(x, y, z)?;
+		Some(self.chunks[chunk].blocks[index])
+	}
+
+	/// The light at the given world coordinates, or 0 outside the chunks.
+	pub fn light(&self, kind: LightKind, x: i32, y: i32, z: i32) -> u8 {
+		self.locate(x, y, z).map_or(0, |(chunk, index)| {
+			let chunk = &self.chunks[chunk];
+			match kind {
+				LightKind::Sky => chunk.sky_light.get(index),
+				LightKind::Block => chunk.block_light.get(index),
+			}
+		})
+	}
+
+	fn set_light(
+		&mut self,
+		kind: LightKind,
+		x: i32,
+		y: i32,
+		z: i32,
+		light: u8,
+	) {
+		let Some((chunk, index)) = self.locate(x, y, z) else {
+			return;
+		};
+		let light_array = match kind {
+			LightKind::Sky => &mut self.chunks[chunk].sky_light,
+			LightKind::Block => &mut self.chunks[chunk].block_light,
+		};
+		if light_array.get(index) != light {
+			light_array.set(index, light);
+			self.changed[chunk] = true;
+		}
+	}
+
+	/// The height just above the highest block that dims light in a
+	/// column, below which the sky is not visible. 0 outside the chunks.
+	pub fn height(&self, x: i32, z: i32) -> i32 {
+		(0..CHUNK_HEIGHT as i32)
+			.rev()
+			.find(|&y| {
+				self.block(x, y, z).is_some_and(|block| opacity(block) != 0)
+			})
+			.map_or(0, |y| y + 1)
+	}
+
+	/// The sky light coming straight down a column before any spreads
+	/// sideways. Every block takes its opacity off on the way, so light
+	/// carries on under leaves or glass the way Alpha lights new chunks.
+	fn sky_column(&self, x: i32, z: i32) -> [u8; CHUNK_HEIGHT] {
+		let mut column = [0; CHUNK_HEIGHT];
+		let mut light = MAX_LIGHT;
+		for y in (0..CHUNK_HEIGHT).rev() {
+			let Some(block) = self.block(x, y as i32, z) else {
+				break;
+			};
+			light = light.saturating_sub(opacity(block));
+			if light == 0 {
+				break;
+			}
+			column[y] = light;
+		}
+		column
+	}
+
+	/// The light a block has before any spreads to it. Sky light comes from
+	/// `columns`, which keeps every column it had to work out.
+	fn source(
+		&self,
+		kind: LightKind,
+		(x, y, z): (i32, i32, i32),
+		columns: &mut SkyColumns,
+	) -> u8 {
+		match kind {
+			LightKind::Sky => {
+				let column = columns
+					.entry((x, z))
+					.or_insert_with(|| self.sky_column(x, z));
+				usize::try_from(y)
+					.ok()
+					.and_then(|y| column.get(y))
+					.copied()
+					.unwrap_or(0)
+			}
+			LightKind::Block => self.block(x, y, z).map_or(0, emission),
+		}
+	}
+
+	/// Lights the chunk at the given chunk coordinates from scratch, taking
+	/// in light from its neighbours and spreading its own into them.
+	pub fn light_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
+		let (base_x, base_z) = (chunk_x * 16, chunk_z * 16);
+		if self.locate(base_x, 0, base_z).is_none() {
+			return;
+		}
+
+		// The heights of the chunk's columns and those just around it.
+		const SPAN: i32 = CHUNK_WIDTH as i32 + 2;
+		let mut heights = [0; (SPAN * SPAN) as usize];
+		for (i, height) in heights.iter_mut().enumerate() {
+			let (dx, dz) = (i as i32 % SPAN, i as i32 / SPAN);
+			*height = self.height(base_x + dx - 1, base_z + dz - 1);
+		}
+		let height_at = |x: i32, z: i32| {
+			heights[((z - base_z + 1) * SPAN + x - base_x + 1) as usize]
+		};
+
+		let mut queues = [VecDeque::new(), VecDeque::new()];
+		for x in base_x..base_x + CHUNK_WIDTH as i32 {
+			for z in base_z..base_z + CHUNK_WIDTH as i32 {
+				// Sky light only has to spread from where the neighbouring
+				// columns reach higher, and down from the top of this one.
+				let reach = NEIGHBOURS
+					.iter()
+					.filter(|(_, dy, _)| *dy == 0)
+					.map(|(dx, _, dz)| height_at(x + dx, z + dz))
+					.fold(height_at(x, z), i32::max);
+				let column = self.sky_column(x, z);
+
+				for y in 0..CHUNK_HEIGHT as i32 {
+					let sky = column[y as usize];
+					self.set_light(LightKind::Sky, x, y, z, sky);
+					if sky != 0 && y <= reach {
+						queues[0].push_back((x, y, z));
+					}
+
+					let block = self.block(x, y, z).map_or(0, emission);
+					self.set_light(LightKind::Block, x, y, z, block);
+					if block != 0 {
+						queues[1].push_back((x, y, z));
+					}
+				}
+			}
+		}
+
+		// Light already in the neighbours spreads in across the edges.
+		for i in 0..CHUNK_WIDTH as i32 {
+			let edges = [
+				(base_x - 1, base_z + i),
+				(base_x + 16, base_z + i),
+				(base_x + i, base_z - 1),
+				(base_x + i, base_z + 16),
+			];
+			for (x, z) in edges {
+				for y in 0..CHUNK_HEIGHT as i32 {
+					for (kind, queue) in
+						LightKind::ALL.into_iter().zip(&mut queues)
+					{
+						if self.light(kind, x, y, z) > 1 {
+							queue.push_back((x, y, z));
+						}
+					}
+				}
+			}
+		}
+
+		for (kind, queue) in LightKind::ALL.into_iter().zip(queues) {
+			self.spread(kind, queue);
+		}
+	}
+
+	/// Relights around a block after it changed, darkening whatever it no
+	/// longer lights before spreading light back in.
+	pub fn update(&mut self, x: i32, y: i32, z: i32) {
+		if self.locate(x, y, z).is_none() {
+			return;
+		}
+
+		let mut columns = SkyColumns::new();
+		for kind in LightKind::ALL {
+			let mut changed = vec![(x, y, z)];
+			if kind == LightKind::Sky {
+				// The block may have changed how much sky light comes down
+				// its column, but only underneath it.
+				let column = self.sky_column(x, z);
+				changed.extend(
+					(0..y)
+						.filter(|&y| {
+							self.light(kind, x, y, z) != column[y as usize]
+						})
+						.map(|y| (x, y, z)),
+				);
+				columns.insert((x, z), column);
+			}
+
+			let mut darken = VecDeque::new();
+			let mut relight = VecDeque::new();
+			for (x, y, z) in changed {
+				let old = self.light(kind, x, y, z);
+				self.set_light(kind, x, y, z, 0);
+				if old != 0 {
+					darken.push_back((x, y, z, old));
+				}
+				let source = self.source(kind, (x, y, z), &mut columns);
+				if source != 0 {
+					self.set_light(kind, x, y, z, source);
+					relight.push_back((x, y, z));
+				}
+			}
+
+			while let Some((x, y, z, old)) = darken.pop_front() {
+				for (dx, dy, dz) in NEIGHBOURS {
+					let (x, y, z) = (x + dx, y + dy, z + dz);
+					let light = self.light(kind, x, y, z);
+					if light == 0 {
+						continue;
+					}
+					// Anything dimmer may have been lit by the block and
+					// has to go dark too. Anything at least as bright is
+					// lit from elsewhere and spreads back in.
+					if light < old {
+						self.set_light(kind, x, y, z, 0);
+						darken.push_back((x, y, z, light));
+						let source = self.source(kind, (x, y, z), &mut columns);
+						if source != 0 {
+							self.set_light(kind, x, y, z, source);
+							relight.push_back((x, y, z));
+						}
+					} else {
+						relight.push_back((x, y, z));
+					}
+				}
+			}
+
+			self.spread(kind, relight);
+		}
+	}
+
+	/// Spreads light outwards from the queued blocks until it runs out.
+	fn spread(
+		&mut self,
+		kind: LightKind,
+		mut queue: VecDeque<(i32, i32, i32)>,
+	) {
+		while let Some((x, y, z)) = queue.pop_front() {
+			let light = self.light(kind, x, y, z);
+			for (dx, dy, dz) in NEIGHBOURS {
+				let (x, y, z) = (x + dx, y + dy, z + dz);
+				let Some(block) = self.block(x, y, z) else {
+					continue;
+				};
+				let spread = light.saturating_sub(opacity(block).max(1));
+				if spread > self.light(kind, x, y, z) {
+					self.set_light(kind, x, y, z, spread);
+					queue.push_back((x, y, z));
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,49 @@
+//! Ports of the `MathHelper` functions whose exact results world generation
+//! and movement depend on.
+
+/// `MathHelper.floor_double`: rounds towards negative infinity.
+pub fn floor(value: f64) -> i32 {
+	let truncated = value as i32;
+	if value < truncated as f64 {
+		truncated - 1
+	} else {
+		truncated
+	}
+}
+
+/// `MathHelper.sin`, which looks the angle up in a 65536 entry table instead
+/// of computing it. The entry is evaluated on demand rather than stored.
+pub fn sin(angle: f32) -> f32 {
+	table_sin((angle * 10430.38) as i32)
+}
+
+/// `MathHelper.cos`, a quarter turn further along the same table.
+pub fn cos(angle: f32) -> f32 {
+	table_sin((angle * 10430.38 + 16384.0) as i32)
+}
+
+fn table_sin(index: i32) -> f32 {
+	use core::f64::consts::PI;
+
+	let mut x = (index & 0xFFFF) as f64 * PI * 2.0 / 65536.0;
+
+	// Reduce to [-pi/2, pi/2], where the Taylor series converges quickly.
+	if x > PI {
+		x -= 2.0 * PI;
+	}
+	if x > PI / 2.0 {
+		x = PI - x;
+	} else if x < -PI / 2.0 {
+		x = -PI - x;
+	}
+
+	let square = x * x;
+	let mut term = x;
+	let mut sum = x;
+	for n in (2..24).step_by(2) {
+		term *= -square / (n * (n + 1)) as f64;
+		sum += term;
+	}
+
+	sum as f32
+}
@@ -0,0 +1,123 @@
+use crate::Result;
+use std::{
+	collections::BTreeSet,
+	fs,
+	io::ErrorKind,
+	path::{Path, PathBuf},
+};
+
+/// The lists of players and addresses a server keeps next to its world.
+pub struct Lists {
+	pub ops: NameList,
+	/// Who may log in while the whitelist is on.
+	pub whitelist: NameList,
+	pub banned_players: NameList,
+	pub banned_ips: NameList,
+}
+
+impl Lists {
+	/// Reads every list from the working directory.
+	pub fn load() -> Result<Self> {
+		Ok(Self {
+			ops: NameList::load("ops.txt")?,
+			whitelist: NameList::load("white-list.txt")?,
+			banned_players: NameList::load("banned-players.txt")?,
+			banned_ips: NameList::load("banned-ips.txt")?,
+		})
+	}
+}
+
+/// A set of player names or addresses kept in a text file with one entry
+/// per line, such as `ops.txt`. Entries compare case-insensitively, like
+/// the Alpha server does, and every change is written back to the file
+/// right away.
+pub struct NameList {
+	path: PathBuf,
+	names: BTreeSet<String>,
+}
+
+impl NameList {
+	/// Reads the list from `path`. A missing file is an empty list.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+		let path = path.as_ref().to_path_buf();
+		let names = match fs::read_to_string(&path) {
+			Ok(text) => text
+				.lines()
+				.map(str::trim)
+				.filter(|line| !line.is_empty() && !line.starts_with('#'))
+				.map(str::to_lowercase)
+				.collect(),
+			Err(err) if err.kind() == ErrorKind::NotFound => BTreeSet::new(),
+			Err(err) => return Err(err.into()),
+		};
+
+		Ok(Self { path, names })
+	}
+
+	pub fn contains(&self, name: &str) -> bool {
+		self.names.contains(&name.to_lowercase())
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &str> {
+		self.names.iter().map(String::as_str)
+	}
+
+	/// Adds a name, returning `false` if it was already listed. If the file
+	/// cannot be written, the name is not added either.
+	pub fn add(&mut self, name: &str) -> Result<bool> {
+		let name = name.to_lowercase();
+		if !self.names.insert(name.clone()) {
+			return Ok(false);
+		}
+		if let Err(err) = self.save() {
+			self.names.remove(&name);
+			return Err(err);
+		}
+
+		Ok(true)
+	}
+
+	/// Removes a name, returning `false` if it was not listed. If the file
+	/// cannot be written, the name stays listed.
+	pub fn remove(&mut self, name: &str) -> Result<bool> {
+		let name = name.to_lowercase();
+		if !self.names.remove(&name) {
+			return Ok(false);
+		}
+		if let Err(err) = self.save() {
+			self.names.insert(name);
+			return Err(err);
+		}
+
+		Ok(true)
+	}
+
+	fn save(&self) -> Result<()> {
+		let mut text = String::new();
+		for name in &self.names {
+			text.push_str(name);
+			text.push('\n');
+		}
+		fs::write(&self.path, text)?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keeps_the_list_as_it_was_when_saving_fails() {
+		let mut list = NameList {
+			path: PathBuf::from("/nonexistent/ops.txt"),
+			names: BTreeSet::from(["notch".into()]),
+		};
+
+		assert!(list.add("Steve").is_err());
+		assert!(!list.contains("steve"));
+		assert!(list.remove("Notch").is_err());
+		assert!(list.contains("notch"));
+	}
+}
@@ -0,0 +1,453 @@
+//! Named Binary Tag encoding, as used by Alpha world files and the payload
+//! of COMPLEX_ENTITY packets.
+
+use crate::{
+	bytes::{ByteReader, ByteWriter},
+	packets::{read_string, write_string},
+	InvalidGzipSnafu, InvalidTagSnafu, InvalidZlibSnafu, Result,
+	TagTooDeepSnafu,
+};
+use alloc::{string::String, vec::Vec};
+use irox_bits::{Bits, MutBits};
+use miniz_oxide::{
+	deflate::{compress_to_vec, compress_to_vec_zlib},
+	inflate::{
+		decompress_to_vec_with_limit, decompress_to_vec_zlib_with_limit,
+	},
+};
+use snafu::{ensure, OptionExt};
+
+pub const TAG_END: u8 = 0;
+pub const TAG_BYTE: u8 = 1;
+pub const TAG_SHORT: u8 = 2;
+pub const TAG_INT: u8 = 3;
+pub const TAG_LONG: u8 = 4;
+pub const TAG_FLOAT: u8 = 5;
+pub const TAG_DOUBLE: u8 = 6;
+pub const TAG_BYTE_ARRAY: u8 = 7;
+pub const TAG_STRING: u8 = 8;
+pub const TAG_LIST: u8 = 9;
+pub const TAG_COMPOUND: u8 = 10;
+
+/// Lists and compounds nested deeper than this are rejected, so a hostile
+/// payload cannot exhaust the stack.
+pub const MAX_DEPTH: usize = 512;
+
+/// The most elements reserved up front for a byte array or list. Lengths
+/// come from the payload, so anything longer grows as it is actually read.
+/// This is enough for the block array of a chunk.
+const MAX_PREALLOCATION: usize = 32768;
+
+/// The most bytes a compressed file or buffer may inflate to. Chunks are
+/// well under a megabyte, so anything bigger is rejected rather than let a
+/// small payload take up all of memory.
+pub const MAX_DECOMPRESSED_SIZE: usize = 16 * 1024 * 1024;
+
+/// How a serialized tag is wrapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+	/// The bare tag, as sent inside packets.
+	None,
+	/// Used by `level.dat` and Alpha chunk files.
+	Gzip,
+	/// Used by the region files of later versions.
+	Zlib,
+}
+
+impl Compression {
+	/// Guesses the wrapping from the first bytes of a file.
+	pub fn detect(bytes: &[u8]) -> Self {
+		match bytes {
+			[0x1F, 0x8B, ..] => Self::Gzip,
+			[0x78, ..] => Self::Zlib,
+			_ => Self::None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+	Byte(i8),
+	Short(i16),
+	Int(i32),
+	Long(i64),
+	Float(f32),
+	Double(f64),
+	ByteArray(Vec<u8>),
+	String(String),
+	/// A list of tags sharing the element type given by the first field.
+	List(u8, Vec<Tag>),
+	Compound(Compound),
+}
+
+impl Tag {
+	/// Builds a list, taking the element type from the first item. Empty
+	/// lists are written with the byte type, like Alpha does.
+	pub fn list(items: Vec<Tag>) -> Self {
+		let tag_type = items.first().map_or(TAG_BYTE, Tag::id);
+
+		Self::List(tag_type, items)
+	}
+
+	pub fn id(&self) -> u8 {
+		match self {
+			Self::Byte(_) => TAG_BYTE,
+			Self::Short(_) => TAG_SHORT,
+			Self::Int(_) => TAG_INT,
+			Self::Long(_) => TAG_LONG,
+			Self::Float(_) => TAG_FLOAT,
+			Self::Double(_) => TAG_DOUBLE,
+			Self::ByteArray(_) => TAG_BYTE_ARRAY,
+			Self::String(_) => TAG_STRING,
+			Self::List(..) => TAG_LIST,
+			Self::Compound(_) => TAG_COMPOUND,
+		}
+	}
+
+	pub fn as_byte(&self) -> Option<i8> {
+		match self {
+			Self::Byte(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_short(&self) -> Option<i16> {
+		match self {
+			Self::Short(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_int(&self) -> Option<i32> {
+		match self {
+			Self::Int(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_long(&self) -> Option<i64> {
+		match self {
+			Self::Long(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_float(&self) -> Option<f32> {
+		match self {
+			Self::Float(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_double(&self) -> Option<f64> {
+		match self {
+			Self::Double(value) => Some(*value),
+			_ => None,
+		}
+	}
+
+	pub fn as_byte_array(&self) -> Option<&[u8]> {
+		match self {
+			Self::ByteArray(value) => Some(value),
+			_ => None,
+		}
+	}
+
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Self::String(value) => Some(value),
+			_ => None,
+		}
+	}
+
+	pub fn as_list(&self) -> Option<&[Tag]> {
+		match self {
+			Self::List(_, items) => Some(items),
+			_ => None,
+		}
+	}
+
+	pub fn as_compound(&self) -> Option<&Compound> {
+		match self {
+			Self::Compound(value) => Some(value),
+			_ => None,
+		}
+	}
+
+	/// Reads the payload of a tag whose type has already been read.
+	pub fn decode(id: u8, bits: &mut impl Bits) -> Result<Self> {
+		Self::decode_nested(id, bits, 0)
+	}
+
+	fn decode_nested(
+		id: u8,
+		bits: &mut impl Bits,
+		depth: usize,
+	) -> Result<Self> {
+		ensure!(depth <= MAX_DEPTH, TagTooDeepSnafu);
+
+		Ok(match id {
+			TAG_BYTE => Self::Byte(bits.read_u8()? as i8),
+			TAG_SHORT => Self::Short(bits.read_be_u16()? as i16),
+			TAG_INT => Self::Int(bits.read_be_u32()? as i32),
+			TAG_LONG => Self::Long(bits.read_be_u64()? as i64),
+			TAG_FLOAT => Self::Float(bits.read_f32()?),
+			TAG_DOUBLE => Self::Double(bits.read_f64()?),
+			TAG_BYTE_ARRAY => {
+				let length = bits.read_be_u32()? as i32;
+				let mut bytes = Vec::with_capacity(preallocation(length));
+				for _ in 0..length {
+					bytes.push(bits.read_u8()?);
+				}
+				Self::ByteArray(bytes)
+			}
+			TAG_STRING => Self::String(read_string(bits)?),
+			TAG_LIST => {
+				let tag_type = bits.read_u8()?;
+				let length = bits.read_be_u32()? as i32;
+				let mut items = Vec::with_capacity(preallocation(length));
+				for _ in 0..length {
+					items.push(Self::decode_nested(tag_type, bits, depth + 1)?);
+				}
+				Self::List(tag_type, items)
+			}
+			TAG_COMPOUND => {
+				let mut compound = Compound::new();
+				loop {
+					let tag_type = bits.read_u8()?;
+					if tag_type == TAG_END {
+						break;
+					}
+					let name = read_string(bits)?;
+					let tag = Self::decode_nested(tag_type, bits, depth + 1)?;
+					compound.insert(name, tag);
+				}
+				Self::Compound(compound)
+			}
+			id => return InvalidTagSnafu { id }.fail(),
+		})
+	}
+
+	/// Writes the payload of the tag, without its type or name.
+	pub fn encode(&self, bits: &mut impl MutBits) -> Result<()> {
+		match self {
+			Self::Byte(value) => bits.write_u8(*value as u8)?,
+			Self::Short(value) => bits.write_be_i16(*value)?,
+			Self::Int(value) => bits.write_be_i32(*value)?,
+			Self::Long(value) => bits.write_be_u64(*value as u64)?,
+			Self::Float(value) => bits.write_f32(*value)?,
+			Self::Double(value) => bits.write_f64(*value)?,
+			Self::ByteArray(bytes) => {
+				bits.write_be_i32(bytes.len() as i32)?;
+				bits.write_all_bytes(bytes)?;
+			}
+			Self::String(value) => write_string(bits, value)?,
+			Self::List(tag_type, items) => {
+				bits.write_u8(*tag_type)?;
+				bits.write_be_i32(items.len() as i32)?;
+				for item in items {
+					item.encode(bits)?;
+				}
+			}
+			Self::Compound(compound) => {
+				for (name, tag) in compound.iter() {
+					bits.write_u8(tag.id())?;
+					write_string(bits, name)?;
+					tag.encode(bits)?;
+				}
+				bits.write_u8(TAG_END)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// A compound tag. Entries keep the order they were inserted in, so files
+/// are written back the way they were read.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Compound(Vec<(String, Tag)>);
+
+impl Compound {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn get(&self, name: &str) -> Option<&Tag> {
+		self.0
+			.iter()
+			.find(|(entry, _)| entry == name)
+			.map(|(_, tag)| tag)
+	}
+
+	/// Adds an entry, replacing any existing entry with the same name.
+	pub fn insert(&mut self, name: impl Into<String>, tag: Tag) {
+		let name = name.into();
+		match self.0.iter_mut().find(|(entry, _)| *entry == name) {
+			Some((_, existing)) => *existing = tag,
+			None => self.0.push((name, tag)),
+		}
+	}
+
+	/// Builder-style variant of [`Compound::insert`].
+	pub fn with(mut self, name: impl Into<String>, tag: Tag) -> Self {
+		self.insert(name, tag);
+		self
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &Tag)> {
+		self.0.iter().map(|(name, tag)| (name.as_str(), tag))
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+/// Reads a named root tag.
+pub fn read(bits: &mut impl Bits) -> Result<(String, Tag)> {
+	let id = bits.read_u8()?;
+	ensure!(id != TAG_END, InvalidTagSnafu { id });
+	let name = read_string(bits)?;
+
+	Ok((name, Tag::decode(id, bits)?))
+}
+
+/// Writes a named root tag.
+pub fn write(bits: &mut impl MutBits, name: &str, tag: &Tag) -> Result<()> {
+	bits.write_u8(tag.id())?;
+	write_string(bits, name)?;
+
+	tag.encode(bits)
+}
+
+/// Reads a named root tag from a whole file or buffer.
+pub fn from_bytes(
+	bytes: &[u8],
+	compression: Compression,
+) -> Result<(String, Tag)> {
+	let data = match compression {
+		Compression::None => return read(&mut ByteReader::new(bytes)),
+		Compression::Gzip => gzip_decompress(bytes)?,
+		Compression::Zlib => {
+			decompress_to_vec_zlib_with_limit(bytes, MAX_DECOMPRESSED_SIZE)
+				.ok()
+				.context(InvalidZlibSnafu)?
+		}
+	};
+
+	read(&mut ByteReader::new(&data))
+}
+
+/// Serializes a named root tag into a whole file or buffer.
+pub fn to_bytes(
+	name: &str,
+	tag: &Tag,
+	compression: Compression,
+) -> Result<Vec<u8>> {
+	let mut writer = ByteWriter::new();
+	write(&mut writer, name, tag)?;
+
+	Ok(match compression {
+		Compression::None => writer.into_inner(),
+		Compression::Gzip => gzip_compress(writer.as_bytes()),
+		Compression::Zlib => compress_to_vec_zlib(writer.as_bytes(), 6),
+	})
+}
+
+/// How many elements to reserve for a byte array or list of the given length.
+fn preallocation(length: i32) -> usize {
+	(length.max(0) as usize).min(MAX_PREALLOCATION)
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+const GZIP_DEFLATE: u8 = 8;
+const GZIP_FLAG_HCRC: u8 = 0x02;
+const GZIP_FLAG_EXTRA: u8 = 0x04;
+const GZIP_FLAG_NAME: u8 = 0x08;
+const GZIP_FLAG_COMMENT: u8 = 0x10;
+
+fn gzip_compress(data: &[u8]) -> Vec<u8> {
+	let mut output = Vec::with_capacity(data.len() / 2 + 18);
+	output.extend_from_slice(&GZIP_MAGIC);
+	output.extend_from_slice(&[GZIP_DEFLATE, 0, 0, 0, 0, 0, 0, 0]);
+	output.extend_from_slice(&compress_to_vec(data, 6));
+	output.extend_from_slice(&crc32(data).to_le_bytes());
+	output.extend_from_slice(&(data.len() as u32).to_le_bytes());
+
+	output
+}
+
+fn gzip_decompress(bytes: &[u8]) -> Result<Vec<u8>> {
+	ensure!(
+		bytes.len() >= 18
+			&& bytes[..2] == GZIP_MAGIC
+			&& bytes[2] == GZIP_DEFLATE,
+		InvalidGzipSnafu
+	);
+
+	let flags = bytes[3];
+	let mut position = 10;
+	if flags & GZIP_FLAG_EXTRA != 0 {
+		let length = bytes
+			.get(position..position + 2)
+			.context(InvalidGzipSnafu)?;
+		position += 2 + u16::from_le_bytes([length[0], length[1]]) as usize;
+	}
+	for flag in [GZIP_FLAG_NAME, GZIP_FLAG_COMMENT] {
+		if flags & flag != 0 {
+			let end = bytes
+				.get(position..)
+				.and_then(|rest| rest.iter().position(|byte| *byte == 0))
+				.context(InvalidGzipSnafu)?;
+			position += end + 1;
+		}
+	}
+	if flags & GZIP_FLAG_HCRC != 0 {
+		position += 2;
+	}
+
+	let body = bytes
+		.get(position..bytes.len() - 8)
+		.context(InvalidGzipSnafu)?;
+	let data = decompress_to_vec_with_limit(body, MAX_DECOMPRESSED_SIZE)
+		.ok()
+		.context(InvalidGzipSnafu)?;
+
+	let trailer = &bytes[bytes.len() - 8..];
+	let expected_crc =
+		u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+	ensure!(crc32(&data) == expected_crc, InvalidGzipSnafu);
+
+	Ok(data)
+}
+
+const CRC32_TABLE: [u32; 256] = {
+	let mut table = [0u32; 256];
+	let mut i = 0;
+	while i < 256 {
+		let mut crc = i as u32;
+		let mut bit = 0;
+		while bit < 8 {
+			crc = if crc & 1 != 0 {
+				0xEDB8_8320 ^ (crc >> 1)
+			} else {
+				crc >> 1
+			};
+			bit += 1;
+		}
+		table[i] = crc;
+		i += 1;
+	}
+	table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+	!data.iter().fold(!0u32, |crc, byte| {
+		CRC32_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+	})
+}
@@ -0,0 +1,1328 @@
+use crate::{math, InvalidLengthSnafu, ItemStack, Result, UnknownPacketSnafu};
+use alloc::{string::String, vec::Vec};
+use irox_bits::{Bits, MutBits};
+use snafu::OptionExt;
+
+pub const KEEP_ALIVE: u8 = 0x00;
+pub const LOGIN: u8 = 0x01;
+pub const HANDSHAKE: u8 = 0x02;
+pub const CHAT_MESSAGE: u8 = 0x03;
+pub const TIME_UPDATE: u8 = 0x04;
+pub const PLAYER_INVENTORY: u8 = 0x05;
+pub const SPAWN_POSITION: u8 = 0x06;
+pub const USE_ENTITY: u8 = 0x07;
+pub const UPDATE_HEALTH: u8 = 0x08;
+pub const RESPAWN: u8 = 0x09;
+pub const PLAYER: u8 = 0x0A;
+pub const PLAYER_POSITION: u8 = 0x0B;
+pub const PLAYER_LOOK: u8 = 0x0C;
+pub const PLAYER_POSITION_AND_LOOK: u8 = 0x0D;
+pub const PLAYER_DIGGING: u8 = 0x0E;
+pub const PLAYER_BLOCK_PLACEMENT: u8 = 0x0F;
+pub const HOLDING_CHANGE: u8 = 0x10;
+pub const ADD_TO_INVENTORY: u8 = 0x11;
+pub const ANIMATION: u8 = 0x12;
+pub const NAMED_ENTITY_SPAWN: u8 = 0x14;
+pub const PICKUP_SPAWN: u8 = 0x15;
+pub const COLLECT_ITEM: u8 = 0x16;
+pub const ADD_OBJECT_OR_VEHICLE: u8 = 0x17;
+pub const MOB_SPAWN: u8 = 0x18;
+pub const ENTITY_VELOCITY: u8 = 0x1C;
+pub const DESTROY_ENTITY: u8 = 0x1D;
+pub const ENTITY: u8 = 0x1E;
+pub const ENTITY_RELATIVE_MOVE: u8 = 0x1F;
+pub const ENTITY_LOOK: u8 = 0x20;
+pub const ENTITY_LOOK_AND_RELATIVE_MOVE: u8 = 0x21;
+pub const ENTITY_TELEPORT: u8 = 0x22;
+pub const ENTITY_STATUS: u8 = 0x26;
+pub const PRE_CHUNK: u8 = 0x32;
+pub const MAP_CHUNK: u8 = 0x33;
+pub const MULTI_BLOCK_CHANGE: u8 = 0x34;
+pub const BLOCK_CHANGE: u8 = 0x35;
+pub const COMPLEX_ENTITY: u8 = 0x3B;
+pub const EXPLOSION: u8 = 0x3C;
+pub const KICK_OR_DISCONNECT: u8 = 0xFF;
+
+/// Any packet of the Alpha protocol, tagged with the direction it travels.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Packet {
+	Serverbound(ServerboundPacket),
+	Clientbound(ClientboundPacket),
+}
+
+impl Packet {
+	pub fn id(&self) -> u8 {
+		match self {
+			Self::Serverbound(packet) => packet.id(),
+			Self::Clientbound(packet) => packet.id(),
+		}
+	}
+
+	pub fn encode(&self, bits: &mut impl MutBits) -> Result<()> {
+		match self {
+			Self::Serverbound(packet) => packet.encode(bits),
+			Self::Clientbound(packet) => packet.encode(bits),
+		}
+	}
+
+	/// Reads a packet sent by a client. The same ID means something else in
+	/// each direction, so the direction has to be known up front.
+	pub fn decode_serverbound(bits: &mut impl Bits) -> Result<Self> {
+		Ok(Self::Serverbound(ServerboundPacket::decode(bits)?))
+	}
+
+	/// Reads a packet sent by a server.
+	pub fn decode_clientbound(bits: &mut impl Bits) -> Result<Self> {
+		Ok(Self::Clientbound(ClientboundPacket::decode(bits)?))
+	}
+}
+
+impl From<ServerboundPacket> for Packet {
+	fn from(value: ServerboundPacket) -> Self {
+		Self::Serverbound(value)
+	}
+}
+
+impl From<ClientboundPacket> for Packet {
+	fn from(value: ClientboundPacket) -> Self {
+		Self::Clientbound(value)
+	}
+}
+
+/// Packets sent by the client to the server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerboundPacket {
+	KeepAlive,
+	Login {
+		protocol_version: i32,
+		username: String,
+		password: String,
+		map_seed: i64,
+		dimension: i8,
+	},
+	Handshake {
+		username: String,
+	},
+	ChatMessage {
+		message: String,
+	},
+	PlayerInventory {
+		kind: i32,
+		items: Vec<Option<ItemStack>>,
+	},
+	UseEntity {
+		user: i32,
+		target: i32,
+		left_click: bool,
+	},
+	Respawn,
+	Player {
+		on_ground: bool,
+	},
+	PlayerPosition {
+		x: f64,
+		y: f64,
+		stance: f64,
+		z: f64,
+		on_ground: bool,
+	},
+	PlayerLook {
+		yaw: f32,
+		pitch: f32,
+		on_ground: bool,
+	},
+	PlayerPositionAndLook {
+		x: f64,
+		y: f64,
+		stance: f64,
+		z: f64,
+		yaw: f32,
+		pitch: f32,
+		on_ground: bool,
+	},
+	PlayerDigging {
+		status: u8,
+		x: i32,
+		y: i8,
+		z: i32,
+		face: u8,
+	},
+	PlayerBlockPlacement {
+		item_id: i16,
+		x: i32,
+		y: i8,
+		z: i32,
+		direction: i8,
+	},
+	HoldingChange {
+		entity_id: i32,
+		item_id: i16,
+	},
+	Animation {
+		entity_id: i32,
+		animation: u8,
+	},
+	PickupSpawn {
+		entity_id: i32,
+		item_id: i16,
+		count: u8,
+		x: i32,
+		y: i32,
+		z: i32,
+		rotation: i8,
+		pitch: i8,
+		roll: i8,
+	},
+	ComplexEntity {
+		x: i32,
+		y: i16,
+		z: i32,
+		payload: Vec<u8>,
+	},
+	KickOrDisconnect {
+		reason: String,
+	},
+}
+
+impl ServerboundPacket {
+	pub fn id(&self) -> u8 {
+		match self {
+			Self::KeepAlive => KEEP_ALIVE,
+			Self::Login { .. } => LOGIN,
+			Self::Handshake { .. } => HANDSHAKE,
+			Self::ChatMessage { .. } => CHAT_MESSAGE,
+			Self::PlayerInventory { .. } => PLAYER_INVENTORY,
+			Self::UseEntity { .. } => USE_ENTITY,
+			Self::Respawn => RESPAWN,
+			Self::Player { .. } => PLAYER,
+			Self::PlayerPosition { .. } => PLAYER_POSITION,
+			Self::PlayerLook { .. } => PLAYER_LOOK,
+			Self::PlayerPositionAndLook { .. } => PLAYER_POSITION_AND_LOOK,
+			Self::PlayerDigging { .. } => PLAYER_DIGGING,
+			Self::PlayerBlockPlacement { .. } => PLAYER_BLOCK_PLACEMENT,
+			Self::HoldingChange { .. } => HOLDING_CHANGE,
+			Self::Animation { .. } => ANIMATION,
+			Self::PickupSpawn { .. } => PICKUP_SPAWN,
+			Self::ComplexEntity { .. } => COMPLEX_ENTITY,
+			Self::KickOrDisconnect { .. } => KICK_OR_DISCONNECT,
+		}
+	}
+
+	/// Reads the packet ID followed by the packet body.
+	pub fn decode(bits: &mut impl Bits) -> Result<Self> {
+		let id = bits.read_u8()?;
+
+		Ok(match id {
+			KEEP_ALIVE => Self::KeepAlive,
+			LOGIN => Self::Login {
+				protocol_version: read_i32(bits)?,
+				username: read_string(bits)?,
+				password: read_string(bits)?,
+				map_seed: read_i64(bits)?,
+				dimension: read_i8(bits)?,
+			},
+			HANDSHAKE => Self::Handshake {
+				username: read_string(bits)?,
+			},
+			CHAT_MESSAGE => Self::ChatMessage {
+				message: read_string(bits)?,
+			},
+			PLAYER_INVENTORY => {
+				let (kind, items) = read_inventory(bits)?;
+				Self::PlayerInventory { kind, items }
+			}
+			USE_ENTITY => Self::UseEntity {
+				user: read_i32(bits)?,
+				target: read_i32(bits)?,
+				left_click: read_bool(bits)?,
+			},
+			RESPAWN => Self::Respawn,
+			PLAYER => Self::Player {
+				on_ground: read_bool(bits)?,
+			},
+			PLAYER_POSITION => Self::PlayerPosition {
+				x: bits.read_f64()?,
+				y: bits.read_f64()?,
+				stance: bits.read_f64()?,
+				z: bits.read_f64()?,
+				on_ground: read_bool(bits)?,
+			},
+			PLAYER_LOOK => Self::PlayerLook {
+				yaw: bits.read_f32()?,
+				pitch: bits.read_f32()?,
+				on_ground: read_bool(bits)?,
+			},
+			PLAYER_POSITION_AND_LOOK => Self::PlayerPositionAndLook {
+				x: bits.read_f64()?,
+				y: bits.read_f64()?,
+				stance: bits.read_f64()?,
+				z: bits.read_f64()?,
+				yaw: bits.read_f32()?,
+				pitch: bits.read_f32()?,
+				on_ground: read_bool(bits)?,
+			},
+			PLAYER_DIGGING => Self::PlayerDigging {
+				status: bits.read_u8()?,
+				x: read_i32(bits)?,
+				y: read_i8(bits)?,
+				z: read_i32(bits)?,
+				face: bits.read_u8()?,
+			},
+			PLAYER_BLOCK_PLACEMENT => Self::PlayerBlockPlacement {
+				item_id: read_i16(bits)?,
+				x: read_i32(bits)?,
+				y: read_i8(bits)?,
+				z: read_i32(bits)?,
+				direction: read_i8(bits)?,
+			},
+			HOLDING_CHANGE => Self::HoldingChange {
+				entity_id: read_i32(bits)?,
+				item_id: read_i16(bits)?,
+			},
+			ANIMATION => Self::Animation {
+				entity_id: read_i32(bits)?,
+				animation: bits.read_u8()?,
+			},
+			PICKUP_SPAWN => Self::PickupSpawn {
+				entity_id: read_i32(bits)?,
+				item_id: read_i16(bits)?,
+				count: bits.read_u8()?,
+				x: read_i32(bits)?,
+				y: read_i32(bits)?,
+				z: read_i32(bits)?,
+				rotation: read_i8(bits)?,
+				pitch: read_i8(bits)?,
+				roll: read_i8(bits)?,
+			},
+			COMPLEX_ENTITY => {
+				let x = read_i32(bits)?;
+				let y = read_i16(bits)?;
+				let z = read_i32(bits)?;
+				let length = read_length(read_i16(bits)?.into())?;
+				Self::ComplexEntity {
+					x,
+					y,
+					z,
+					payload: read_bytes(bits, length)?,
+				}
+			}
+			KICK_OR_DISCONNECT => Self::KickOrDisconnect {
+				reason: read_string(bits)?,
+			},
+			id => return UnknownPacketSnafu { id }.fail(),
+		})
+	}
+
+	/// Writes the packet ID followed by the packet body.
+	pub fn encode(&self, bits: &mut impl MutBits) -> Result<()> {
+		bits.write_u8(self.id())?;
+
+		match self {
+			Self::KeepAlive | Self::Respawn => {}
+			Self::Login {
+				protocol_version,
+				username,
+				password,
+				map_seed,
+				dimension,
+			} => {
+				bits.write_be_i32(*protocol_version)?;
+				write_string(bits, username)?;
+				write_string(bits, password)?;
+				bits.write_be_u64(*map_seed as u64)?;
+				bits.write_u8(*dimension as u8)?;
+			}
+			Self::Handshake { username } => write_string(bits, username)?,
+			Self::ChatMessage { message } => write_string(bits, message)?,
+			Self::PlayerInventory { kind, items } => {
+				write_inventory(bits, *kind, items)?
+			}
+			Self::UseEntity {
+				user,
+				target,
+				left_click,
+			} => {
+				bits.write_be_i32(*user)?;
+				bits.write_be_i32(*target)?;
+				bits.write_u8(*left_click as u8)?;
+			}
+			Self::Player { on_ground } => bits.write_u8(*on_ground as u8)?,
+			Self::PlayerPosition {
+				x,
+				y,
+				stance,
+				z,
+				on_ground,
+			} => {
+				bits.write_f64(*x)?;
+				bits.write_f64(*y)?;
+				bits.write_f64(*stance)?;
+				bits.write_f64(*z)?;
+				bits.write_u8(*on_ground as u8)?;
+			}
+			Self::PlayerLook {
+				yaw,
+				pitch,
+				on_ground,
+			} => {
+				bits.write_f32(*yaw)?;
+				bits.write_f32(*pitch)?;
+				bits.write_u8(*on_ground as u8)?;
+			}
+			Self::PlayerPositionAndLook {
+				x,
+				y,
+				stance,
+				z,
+				yaw,
+				pitch,
+				on_ground,
+			} => {
+				bits.write_f64(*x)?;
+				bits.write_f64(*y)?;
+				bits.write_f64(*stance)?;
+				bits.write_f64(*z)?;
+				bits.write_f32(*yaw)?;
+				bits.write_f32(*pitch)?;
+				bits.write_u8(*on_ground as u8)?;
+			}
+			Self::PlayerDigging {
+				status,
+				x,
+				y,
+				z,
+				face,
+			} => {
+				bits.write_u8(*status)?;
+				bits.write_be_i32(*x)?;
+				bits.write_u8(*y as u8)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*face)?;
+			}
+			Self::PlayerBlockPlacement {
+				item_id,
+				x,
+				y,
+				z,
+				direction,
+			} => {
+				bits.write_be_i16(*item_id)?;
+				bits.write_be_i32(*x)?;
+				bits.write_u8(*y as u8)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*direction as u8)?;
+			}
+			Self::HoldingChange { entity_id, item_id } => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_be_i16(*item_id)?;
+			}
+			Self::Animation {
+				entity_id,
+				animation,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_u8(*animation)?;
+			}
+			Self::PickupSpawn {
+				entity_id,
+				item_id,
+				count,
+				x,
+				y,
+				z,
+				rotation,
+				pitch,
+				roll,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_be_i16(*item_id)?;
+				bits.write_u8(*count)?;
+				bits.write_be_i32(*x)?;
+				bits.write_be_i32(*y)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*rotation as u8)?;
+				bits.write_u8(*pitch as u8)?;
+				bits.write_u8(*roll as u8)?;
+			}
+			Self::ComplexEntity { x, y, z, payload } => {
+				bits.write_be_i32(*x)?;
+				bits.write_be_i16(*y)?;
+				bits.write_be_i32(*z)?;
+				bits.write_be_i16(write_length(payload.len())?)?;
+				bits.write_all_bytes(payload)?;
+			}
+			Self::KickOrDisconnect { reason } => write_string(bits, reason)?,
+		}
+
+		Ok(())
+	}
+}
+
+/// Packets sent by the server to the client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientboundPacket {
+	KeepAlive,
+	Login {
+		entity_id: i32,
+		server_name: String,
+		motd: String,
+		map_seed: i64,
+		dimension: i8,
+	},
+	Handshake {
+		connection_hash: String,
+	},
+	ChatMessage {
+		message: String,
+	},
+	TimeUpdate {
+		time: i64,
+	},
+	PlayerInventory {
+		kind: i32,
+		items: Vec<Option<ItemStack>>,
+	},
+	SpawnPosition {
+		x: i32,
+		y: i32,
+		z: i32,
+	},
+	UpdateHealth {
+		health: i16,
+	},
+	Respawn,
+	PlayerPositionAndLook {
+		x: f64,
+		stance: f64,
+		y: f64,
+		z: f64,
+		yaw: f32,
+		pitch: f32,
+		on_ground: bool,
+	},
+	HoldingChange {
+		entity_id: i32,
+		item_id: i16,
+	},
+	AddToInventory {
+		item: ItemStack,
+	},
+	Animation {
+		entity_id: i32,
+		animation: u8,
+	},
+	NamedEntitySpawn {
+		entity_id: i32,
+		name: String,
+		x: i32,
+		y: i32,
+		z: i32,
+		rotation: i8,
+		pitch: i8,
+		current_item: i16,
+	},
+	PickupSpawn {
+		entity_id: i32,
+		item_id: i16,
+		count: u8,
+		x: i32,
+		y: i32,
+		z: i32,
+		rotation: i8,
+		pitch: i8,
+		roll: i8,
+	},
+	CollectItem {
+		collected_entity_id: i32,
+		collector_entity_id: i32,
+	},
+	AddObjectOrVehicle {
+		entity_id: i32,
+		kind: u8,
+		x: i32,
+		y: i32,
+		z: i32,
+	},
+	MobSpawn {
+		entity_id: i32,
+		kind: u8,
+		x: i32,
+		y: i32,
+		z: i32,
+		yaw: i8,
+		pitch: i8,
+	},
+	EntityVelocity {
+		entity_id: i32,
+		velocity_x: i16,
+		velocity_y: i16,
+		velocity_z: i16,
+	},
+	DestroyEntity {
+		entity_id: i32,
+	},
+	Entity {
+		entity_id: i32,
+	},
+	EntityRelativeMove {
+		entity_id: i32,
+		dx: i8,
+		dy: i8,
+		dz: i8,
+	},
+	EntityLook {
+		entity_id: i32,
+		yaw: i8,
+		pitch: i8,
+	},
+	EntityLookAndRelativeMove {
+		entity_id: i32,
+		dx: i8,
+		dy: i8,
+		dz: i8,
+		yaw: i8,
+		pitch: i8,
+	},
+	EntityTeleport {
+		entity_id: i32,
+		x: i32,
+		y: i32,
+		z: i32,
+		yaw: i8,
+		pitch: i8,
+	},
+	EntityStatus {
+		entity_id: i32,
+		status: u8,
+	},
+	PreChunk {
+		x: i32,
+		z: i32,
+		load: bool,
+	},
+	MapChunk {
+		x: i32,
+		y: i16,
+		z: i32,
+		size_x: u8,
+		size_y: u8,
+		size_z: u8,
+		compressed_data: Vec<u8>,
+	},
+	MultiBlockChange {
+		chunk_x: i32,
+		chunk_z: i32,
+		coordinates: Vec<i16>,
+		block_types: Vec<u8>,
+		metadata: Vec<u8>,
+	},
+	BlockChange {
+		x: i32,
+		y: i8,
+		z: i32,
+		block_type: u8,
+		metadata: u8,
+	},
+	ComplexEntity {
+		x: i32,
+		y: i16,
+		z: i32,
+		payload: Vec<u8>,
+	},
+	Explosion {
+		x: f64,
+		y: f64,
+		z: f64,
+		radius: f32,
+		records: Vec<(i8, i8, i8)>,
+	},
+	KickOrDisconnect {
+		reason: String,
+	},
+}
+
+impl ClientboundPacket {
+	pub fn id(&self) -> u8 {
+		match self {
+			Self::KeepAlive => KEEP_ALIVE,
+			Self::Login { .. } => LOGIN,
+			Self::Handshake { .. } => HANDSHAKE,
+			Self::ChatMessage { .. } => CHAT_MESSAGE,
+			Self::TimeUpdate { .. } => TIME_UPDATE,
+			Self::PlayerInventory { .. } => PLAYER_INVENTORY,
+			Self::SpawnPosition { .. } => SPAWN_POSITION,
+			Self::UpdateHealth { .. } => UPDATE_HEALTH,
+			Self::Respawn => RESPAWN,
+			Self::PlayerPositionAndLook { .. } => PLAYER_POSITION_AND_LOOK,
+			Self::HoldingChange { .. } => HOLDING_CHANGE,
+			Self::AddToInventory { .. } => ADD_TO_INVENTORY,
+			Self::Animation { .. } => ANIMATION,
+			Self::NamedEntitySpawn { .. } => NAMED_ENTITY_SPAWN,
+			Self::PickupSpawn { .. } => PICKUP_SPAWN,
+			Self::CollectItem { .. } => COLLECT_ITEM,
+			Self::AddObjectOrVehicle { .. } => ADD_OBJECT_OR_VEHICLE,
+			Self::MobSpawn { .. } => MOB_SPAWN,
+			Self::EntityVelocity { .. } => ENTITY_VELOCITY,
+			Self::DestroyEntity { .. } => DESTROY_ENTITY,
+			Self::Entity { .. } => ENTITY,
+			Self::EntityRelativeMove { .. } => ENTITY_RELATIVE_MOVE,
+			Self::EntityLook { .. } => ENTITY_LOOK,
+			Self::EntityLookAndRelativeMove { .. } => {
+				ENTITY_LOOK_AND_RELATIVE_MOVE
+			}
+			Self::EntityTeleport { .. } => ENTITY_TELEPORT,
+			Self::EntityStatus { .. } => ENTITY_STATUS,
+			Self::PreChunk { .. } => PRE_CHUNK,
+			Self::MapChunk { .. } => MAP_CHUNK,
+			Self::MultiBlockChange { .. } => MULTI_BLOCK_CHANGE,
+			Self::BlockChange { .. } => BLOCK_CHANGE,
+			Self::ComplexEntity { .. } => COMPLEX_ENTITY,
+			Self::Explosion { .. } => EXPLOSION,
+			Self::KickOrDisconnect { .. } => KICK_OR_DISCONNECT,
+		}
+	}
+
+	/// Reads the packet ID followed by the packet body.
+	pub fn decode(bits: &mut impl Bits) -> Result<Self> {
+		let id = bits.read_u8()?;
+
+		Ok(match id {
+			KEEP_ALIVE => Self::KeepAlive,
+			LOGIN => Self::Login {
+				entity_id: read_i32(bits)?,
+				server_name: read_string(bits)?,
+				motd: read_string(bits)?,
+				map_seed: read_i64(bits)?,
+				dimension: read_i8(bits)?,
+			},
+			HANDSHAKE => Self::Handshake {
+				connection_hash: read_string(bits)?,
+			},
+			CHAT_MESSAGE => Self::ChatMessage {
+				message: read_string(bits)?,
+			},
+			TIME_UPDATE => Self::TimeUpdate {
+				time: read_i64(bits)?,
+			},
+			PLAYER_INVENTORY => {
+				let (kind, items) = read_inventory(bits)?;
+				Self::PlayerInventory { kind, items }
+			}
+			SPAWN_POSITION => Self::SpawnPosition {
+				x: read_i32(bits)?,
+				y: read_i32(bits)?,
+				z: read_i32(bits)?,
+			},
+			UPDATE_HEALTH => Self::UpdateHealth {
+				health: read_i16(bits)?,
+			},
+			RESPAWN => Self::Respawn,
+			PLAYER_POSITION_AND_LOOK => Self::PlayerPositionAndLook {
+				x: bits.read_f64()?,
+				stance: bits.read_f64()?,
+				y: bits.read_f64()?,
+				z: bits.read_f64()?,
+				yaw: bits.read_f32()?,
+				pitch: bits.read_f32()?,
+				on_ground: read_bool(bits)?,
+			},
+			HOLDING_CHANGE => Self::HoldingChange {
+				entity_id: read_i32(bits)?,
+				item_id: read_i16(bits)?,
+			},
+			ADD_TO_INVENTORY => Self::AddToInventory {
+				item: ItemStack {
+					id: read_i16(bits)?,
+					count: bits.read_u8()?,
+					damage: read_i16(bits)?,
+				},
+			},
+			ANIMATION => Self::Animation {
+				entity_id: read_i32(bits)?,
+				animation: bits.read_u8()?,
+			},
+			NAMED_ENTITY_SPAWN => Self::NamedEntitySpawn {
+				entity_id: read_i32(bits)?,
+				name: read_string(bits)?,
+				x: read_i32(bits)?,
+				y: read_i32(bits)?,
+				z: read_i32(bits)?,
+				rotation: read_i8(bits)?,
+				pitch: read_i8(bits)?,
+				current_item: read_i16(bits)?,
+			},
+			PICKUP_SPAWN => Self::PickupSpawn {
+				entity_id: read_i32(bits)?,
+				item_id: read_i16(bits)?,
+				count: bits.read_u8()?,
+				x: read_i32(bits)?,
+				y: read_i32(bits)?,
+				z: read_i32(bits)?,
+				rotation: read_i8(bits)?,
+				pitch: read_i8(bits)?,
+				roll: read_i8(bits)?,
+			},
+			COLLECT_ITEM => Self::CollectItem {
+				collected_entity_id: read_i32(bits)?,
+				collector_entity_id: read_i32(bits)?,
+			},
+			ADD_OBJECT_OR_VEHICLE => Self::AddObjectOrVehicle {
+				entity_id: read_i32(bits)?,
+				kind: bits.read_u8()?,
+				x: read_i32(bits)?,
+				y: read_i32(bits)?,
+				z: read_i32(bits)?,
+			},
+			MOB_SPAWN => Self::MobSpawn {
+				entity_id: read_i32(bits)?,
+				kind: bits.read_u8()?,
+				x: read_i32(bits)?,
+				y: read_i32(bits)?,
+				z: read_i32(bits)?,
+				yaw: read_i8(bits)?,
+				pitch: read_i8(bits)?,
+			},
+			ENTITY_VELOCITY => Self::EntityVelocity {
+				entity_id: read_i32(bits)?,
+				velocity_x: read_i16(bits)?,
+				velocity_y: read_i16(bits)?,
+				velocity_z: read_i16(bits)?,
+			},
+			DESTROY_ENTITY => Self::DestroyEntity {
+				entity_id: read_i32(bits)?,
+			},
+			ENTITY => Self::Entity {
+				entity_id: read_i32(bits)?,
+			},
+			ENTITY_RELATIVE_MOVE => Self::EntityRelativeMove {
+				entity_id: read_i32(bits)?,
+				dx: read_i8(bits)?,
+				dy: read_i8(bits)?,
+				dz: read_i8(bits)?,
+			},
+			ENTITY_LOOK => Self::EntityLook {
+				entity_id: read_i32(bits)?,
+				yaw: read_i8(bits)?,
+				pitch: read_i8(bits)?,
+			},
+			ENTITY_LOOK_AND_RELATIVE_MOVE => Self::EntityLookAndRelativeMove {
+				entity_id: read_i32(bits)?,
+				dx: read_i8(bits)?,
+				dy: read_i8(bits)?,
+				dz: read_i8(bits)?,
+				yaw: read_i8(bits)?,
+				pitch: read_i8(bits)?,
+			},
+			ENTITY_TELEPORT => Self::EntityTeleport {
+				entity_id: read_i32(bits)?,
+				x: read_i32(bits)?,
+				y: read_i32(bits)?,
+				z: read_i32(bits)?,
+				yaw: read_i8(bits)?,
+				pitch: read_i8(bits)?,
+			},
+			ENTITY_STATUS => Self::EntityStatus {
+				entity_id: read_i32(bits)?,
+				status: bits.read_u8()?,
+			},
+			PRE_CHUNK => Self::PreChunk {
+				x: read_i32(bits)?,
+				z: read_i32(bits)?,
+				load: read_bool(bits)?,
+			},
+			MAP_CHUNK => {
+				let x = read_i32(bits)?;
+				let y = read_i16(bits)?;
+				let z = read_i32(bits)?;
+				let size_x = bits.read_u8()?;
+				let size_y = bits.read_u8()?;
+				let size_z = bits.read_u8()?;
+				let length = read_length(read_i32(bits)?)?;
+				Self::MapChunk {
+					x,
+					y,
+					z,
+					size_x,
+					size_y,
+					size_z,
+					compressed_data: read_bytes(bits, length)?,
+				}
+			}
+			MULTI_BLOCK_CHANGE => {
+				let chunk_x = read_i32(bits)?;
+				let chunk_z = read_i32(bits)?;
+				let length = read_length(read_i16(bits)?.into())?;
+				let mut coordinates = Vec::new();
+				for _ in 0..length {
+					coordinates.push(read_i16(bits)?);
+				}
+				Self::MultiBlockChange {
+					chunk_x,
+					chunk_z,
+					coordinates,
+					block_types: read_bytes(bits, length)?,
+					metadata: read_bytes(bits, length)?,
+				}
+			}
+			BLOCK_CHANGE => Self::BlockChange {
+				x: read_i32(bits)?,
+				y: read_i8(bits)?,
+				z: read_i32(bits)?,
+				block_type: bits.read_u8()?,
+				metadata: bits.read_u8()?,
+			},
+			COMPLEX_ENTITY => {
+				let x = read_i32(bits)?;
+				let y = read_i16(bits)?;
+				let z = read_i32(bits)?;
+				let length = read_length(read_i16(bits)?.into())?;
+				Self::ComplexEntity {
+					x,
+					y,
+					z,
+					payload: read_bytes(bits, length)?,
+				}
+			}
+			EXPLOSION => {
+				let x = bits.read_f64()?;
+				let y = bits.read_f64()?;
+				let z = bits.read_f64()?;
+				let radius = bits.read_f32()?;
+				let length = read_length(read_i32(bits)?)?;
+				let mut records = Vec::new();
+				for _ in 0..length {
+					records.push((
+						read_i8(bits)?,
+						read_i8(bits)?,
+						read_i8(bits)?,
+					));
+				}
+				Self::Explosion {
+					x,
+					y,
+					z,
+					radius,
+					records,
+				}
+			}
+			KICK_OR_DISCONNECT => Self::KickOrDisconnect {
+				reason: read_string(bits)?,
+			},
+			id => return UnknownPacketSnafu { id }.fail(),
+		})
+	}
+
+	/// Writes the packet ID followed by the packet body.
+	pub fn encode(&self, bits: &mut impl MutBits) -> Result<()> {
+		bits.write_u8(self.id())?;
+
+		match self {
+			Self::KeepAlive | Self::Respawn => {}
+			Self::Login {
+				entity_id,
+				server_name,
+				motd,
+				map_seed,
+				dimension,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				write_string(bits, server_name)?;
+				write_string(bits, motd)?;
+				bits.write_be_u64(*map_seed as u64)?;
+				bits.write_u8(*dimension as u8)?;
+			}
+			Self::Handshake { connection_hash } => {
+				write_string(bits, connection_hash)?
+			}
+			Self::ChatMessage { message } => write_string(bits, message)?,
+			Self::TimeUpdate { time } => bits.write_be_u64(*time as u64)?,
+			Self::PlayerInventory { kind, items } => {
+				write_inventory(bits, *kind, items)?
+			}
+			Self::SpawnPosition { x, y, z } => {
+				bits.write_be_i32(*x)?;
+				bits.write_be_i32(*y)?;
+				bits.write_be_i32(*z)?;
+			}
+			Self::UpdateHealth { health } => bits.write_be_i16(*health)?,
+			Self::PlayerPositionAndLook {
+				x,
+				stance,
+				y,
+				z,
+				yaw,
+				pitch,
+				on_ground,
+			} => {
+				bits.write_f64(*x)?;
+				bits.write_f64(*stance)?;
+				bits.write_f64(*y)?;
+				bits.write_f64(*z)?;
+				bits.write_f32(*yaw)?;
+				bits.write_f32(*pitch)?;
+				bits.write_u8(*on_ground as u8)?;
+			}
+			Self::HoldingChange { entity_id, item_id } => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_be_i16(*item_id)?;
+			}
+			Self::AddToInventory { item } => {
+				bits.write_be_i16(item.id)?;
+				bits.write_u8(item.count)?;
+				bits.write_be_i16(item.damage)?;
+			}
+			Self::Animation {
+				entity_id,
+				animation,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_u8(*animation)?;
+			}
+			Self::NamedEntitySpawn {
+				entity_id,
+				name,
+				x,
+				y,
+				z,
+				rotation,
+				pitch,
+				current_item,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				write_string(bits, name)?;
+				bits.write_be_i32(*x)?;
+				bits.write_be_i32(*y)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*rotation as u8)?;
+				bits.write_u8(*pitch as u8)?;
+				bits.write_be_i16(*current_item)?;
+			}
+			Self::PickupSpawn {
+				entity_id,
+				item_id,
+				count,
+				x,
+				y,
+				z,
+				rotation,
+				pitch,
+				roll,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_be_i16(*item_id)?;
+				bits.write_u8(*count)?;
+				bits.write_be_i32(*x)?;
+				bits.write_be_i32(*y)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*rotation as u8)?;
+				bits.write_u8(*pitch as u8)?;
+				bits.write_u8(*roll as u8)?;
+			}
+			Self::CollectItem {
+				collected_entity_id,
+				collector_entity_id,
+			} => {
+				bits.write_be_i32(*collected_entity_id)?;
+				bits.write_be_i32(*collector_entity_id)?;
+			}
+			Self::AddObjectOrVehicle {
+				entity_id,
+				kind,
+				x,
+				y,
+				z,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_u8(*kind)?;
+				bits.write_be_i32(*x)?;
+				bits.write_be_i32(*y)?;
+				bits.write_be_i32(*z)?;
+			}
+			Self::MobSpawn {
+				entity_id,
+				kind,
+				x,
+				y,
+				z,
+				yaw,
+				pitch,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_u8(*kind)?;
+				bits.write_be_i32(*x)?;
+				bits.write_be_i32(*y)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*yaw as u8)?;
+				bits.write_u8(*pitch as u8)?;
+			}
+			Self::EntityVelocity {
+				entity_id,
+				velocity_x,
+				velocity_y,
+				velocity_z,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_be_i16(*velocity_x)?;
+				bits.write_be_i16(*velocity_y)?;
+				bits.write_be_i16(*velocity_z)?;
+			}
+			Self::DestroyEntity { entity_id } | Self::Entity { entity_id } => {
+				bits.write_be_i32(*entity_id)?
+			}
+			Self::EntityRelativeMove {
+				entity_id,
+				dx,
+				dy,
+				dz,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_u8(*dx as u8)?;
+				bits.write_u8(*dy as u8)?;
+				bits.write_u8(*dz as u8)?;
+			}
+			Self::EntityLook {
+				entity_id,
+				yaw,
+				pitch,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_u8(*yaw as u8)?;
+				bits.write_u8(*pitch as u8)?;
+			}
+			Self::EntityLookAndRelativeMove {
+				entity_id,
+				dx,
+				dy,
+				dz,
+				yaw,
+				pitch,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_u8(*dx as u8)?;
+				bits.write_u8(*dy as u8)?;
+				bits.write_u8(*dz as u8)?;
+				bits.write_u8(*yaw as u8)?;
+				bits.write_u8(*pitch as u8)?;
+			}
+			Self::EntityTeleport {
+				entity_id,
+				x,
+				y,
+				z,
+				yaw,
+				pitch,
+			} => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_be_i32(*x)?;
+				bits.write_be_i32(*y)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*yaw as u8)?;
+				bits.write_u8(*pitch as u8)?;
+			}
+			Self::EntityStatus { entity_id, status } => {
+				bits.write_be_i32(*entity_id)?;
+				bits.write_u8(*status)?;
+			}
+			Self::PreChunk { x, z, load } => {
+				bits.write_be_i32(*x)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*load as u8)?;
+			}
+			Self::MapChunk {
+				x,
+				y,
+				z,
+				size_x,
+				size_y,
+				size_z,
+				compressed_data,
+			} => {
+				bits.write_be_i32(*x)?;
+				bits.write_be_i16(*y)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*size_x)?;
+				bits.write_u8(*size_y)?;
+				bits.write_u8(*size_z)?;
+				bits.write_be_i32(write_length(compressed_data.len())?)?;
+				bits.write_all_bytes(compressed_data)?;
+			}
+			Self::MultiBlockChange {
+				chunk_x,
+				chunk_z,
+				coordinates,
+				block_types,
+				metadata,
+			} => {
+				bits.write_be_i32(*chunk_x)?;
+				bits.write_be_i32(*chunk_z)?;
+				bits.write_be_i16(write_length(coordinates.len())?)?;
+				for coordinate in coordinates {
+					bits.write_be_i16(*coordinate)?;
+				}
+				bits.write_all_bytes(block_types)?;
+				bits.write_all_bytes(metadata)?;
+			}
+			Self::BlockChange {
+				x,
+				y,
+				z,
+				block_type,
+				metadata,
+			} => {
+				bits.write_be_i32(*x)?;
+				bits.write_u8(*y as u8)?;
+				bits.write_be_i32(*z)?;
+				bits.write_u8(*block_type)?;
+				bits.write_u8(*metadata)?;
+			}
+			Self::ComplexEntity { x, y, z, payload } => {
+				bits.write_be_i32(*x)?;
+				bits.write_be_i16(*y)?;
+				bits.write_be_i32(*z)?;
+				bits.write_be_i16(write_length(payload.len())?)?;
+				bits.write_all_bytes(payload)?;
+			}
+			Self::Explosion {
+				x,
+				y,
+				z,
+				radius,
+				records,
+			} => {
+				bits.write_f64(*x)?;
+				bits.write_f64(*y)?;
+				bits.write_f64(*z)?;
+				bits.write_f32(*radius)?;
+				bits.write_be_i32(write_length(records.len())?)?;
+				for (dx, dy, dz) in records {
+					bits.write_u8(*dx as u8)?;
+					bits.write_u8(*dy as u8)?;
+					bits.write_u8(*dz as u8)?;
+				}
+			}
+			Self::KickOrDisconnect { reason } => write_string(bits, reason)?,
+		}
+
+		Ok(())
+	}
+}
+
+/// Encodes an entity coordinate as the fixed-point integer, in 1/32 of a
+/// block, that spawn and movement packets carry.
+pub fn to_fixed_point(value: f64) -> i32 {
+	math::floor(value * 32.0)
+}
+
+/// Encodes an angle in degrees as a byte, in 1/256 of a turn.
+pub fn to_angle(degrees: f32) -> i8 {
+	(degrees * 256.0 / 360.0) as i32 as i8
+}
+
+fn read_bool(bits: &mut impl Bits) -> Result<bool> {
+	Ok(bits.read_u8()? != 0)
+}
+
+fn read_i8(bits: &mut impl Bits) -> Result<i8> {
+	Ok(bits.read_u8()? as i8)
+}
+
+fn read_i16(bits: &mut impl Bits) -> Result<i16> {
+	Ok(bits.read_be_u16()? as i16)
+}
+
+fn read_i32(bits: &mut impl Bits) -> Result<i32> {
+	Ok(bits.read_be_u32()? as i32)
+}
+
+fn read_i64(bits: &mut impl Bits) -> Result<i64> {
+	Ok(bits.read_be_u64()? as i64)
+}
+
+/// Checks a length the peer sent ahead of a list, which cannot be negative.
+/// Lists are not allocated up front from it, since nothing stops it from
+/// being far larger than what follows.
+fn read_length(length: i32) -> Result<usize> {
+	usize::try_from(length).ok().context(InvalidLengthSnafu {
+		length: i64::from(length),
+	})
+}
+
+/// Converts the length of a list or string to the type the protocol sends
+/// ahead of it, failing if it is too long to be described by one.
+fn write_length<T: TryFrom<usize>>(length: usize) -> Result<T> {
+	T::try_from(length).ok().context(InvalidLengthSnafu {
+		length: length as i64,
+	})
+}
+
+fn read_bytes(bits: &mut impl Bits, length: usize) -> Result<Vec<u8>> {
+	let mut bytes = Vec::new();
+	for _ in 0..length {
+		bytes.push(bits.read_u8()?);
+	}
+
+	Ok(bytes)
+}
+
+/// Reads a string prefixed with its length as a big-endian `u16`.
+pub fn read_string(bits: &mut impl Bits) -> Result<String> {
+	let length = bits.read_be_u16()?;
+
+	Ok(bits.read_str_sized_lossy(length as usize)?)
+}
+
+/// Writes a string prefixed with its length as a big-endian `u16`, failing
+/// if it is too long for that.
+pub fn write_string(bits: &mut impl MutBits, value: &str) -> Result<()> {
+	bits.write_be_u16(write_length(value.len())?)?;
+	bits.write_all_bytes(value.as_bytes())?;
+
+	Ok(())
+}
+
+/// Inventory contents are a window type followed by a list of slots, where
+/// an item ID of `-1` marks an empty slot.
+fn read_inventory(
+	bits: &mut impl Bits,
+) -> Result<(i32, Vec<Option<ItemStack>>)> {
+	let kind = read_i32(bits)?;
+	let length = read_length(read_i16(bits)?.into())?;
+
+	let mut items = Vec::new();
+	for _ in 0..length {
+		let id = read_i16(bits)?;
+		if id < 0 {
+			items.push(None);
+		} else {
+			items.push(Some(ItemStack {
+				id,
+				count: bits.read_u8()?,
+				damage: read_i16(bits)?,
+			}));
+		}
+	}
+
+	Ok((kind, items))
+}
+
+fn write_inventory(
+	bits: &mut impl MutBits,
+	kind: i32,
+	items: &[Option<ItemStack>],
+) -> Result<()> {
+	bits.write_be_i32(kind)?;
+	bits.write_be_i16(write_length(items.len())?)?;
+
+	for item in items {
+		match item {
+			Some(item) => {
+				bits.write_be_i16(item.id)?;
+				bits.write_u8(item.count)?;
+				bits.write_be_i16(item.damage)?;
+			}
+			None => bits.write_be_i16(-1)?,
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,64 @@
+/// A port of `java.util.Random`, so seeded generation matches what the
+/// original game derives from the same seed.
+#[derive(Debug, Clone)]
+pub struct JavaRandom {
+	seed: i64,
+}
+
+const MULTIPLIER: i64 = 0x5_DEEC_E66D;
+const ADDEND: i64 = 0xB;
+const MASK: i64 = (1 << 48) - 1;
+
+impl JavaRandom {
+	pub fn new(seed: i64) -> Self {
+		let mut random = Self { seed: 0 };
+		random.set_seed(seed);
+		random
+	}
+
+	pub fn set_seed(&mut self, seed: i64) {
+		self.seed = (seed ^ MULTIPLIER) & MASK;
+	}
+
+	fn next(&mut self, bits: u32) -> i32 {
+		self.seed =
+			self.seed.wrapping_mul(MULTIPLIER).wrapping_add(ADDEND) & MASK;
+
+		(self.seed >> (48 - bits)) as i32
+	}
+
+	/// Returns a value in `0..bound`. Panics if `bound` is not positive, like
+	/// Java throws.
+	pub fn next_int(&mut self, bound: i32) -> i32 {
+		assert!(bound > 0, "bound must be positive");
+
+		if bound & -bound == bound {
+			return ((bound as i64 * self.next(31) as i64) >> 31) as i32;
+		}
+
+		loop {
+			let bits = self.next(31);
+			let value = bits % bound;
+			if bits.wrapping_sub(value).wrapping_add(bound - 1) >= 0 {
+				return value;
+			}
+		}
+	}
+
+	pub fn next_long(&mut self) -> i64 {
+		((self.next(32) as i64) << 32).wrapping_add(self.next(32) as i64)
+	}
+
+	pub fn next_bool(&mut self) -> bool {
+		self.next(1) != 0
+	}
+
+	pub fn next_float(&mut self) -> f32 {
+		self.next(24) as f32 / (1 << 24) as f32
+	}
+
+	pub fn next_double(&mut self) -> f64 {
+		(((self.next(26) as i64) << 27) + self.next(27) as i64) as f64
+			* (1.0 / (1i64 << 53) as f64)
+	}
+}
@@ -0,0 +1,1670 @@
+use crate::{
+	command::{Arguments, Commands, Permission, Sender},
+	config::Config,
+	names::Lists,
+	world::World,
+};
+use oxidized_alpha::{
+	blocks,
+	chunk::CHUNK_HEIGHT,
+	entity::ItemEntity,
+	health::Surroundings,
+	inventory::Section,
+	items, math,
+	packets::{to_angle, to_fixed_point, ClientboundPacket, ServerboundPacket},
+	random::JavaRandom,
+	Health, Inventory, ItemStack, Player,
+};
+use std::{
+	collections::{hash_map::RandomState, HashMap, HashSet},
+	hash::{BuildHasher, Hasher},
+	net::{IpAddr, SocketAddr},
+	sync::{
+		atomic::{AtomicI32, Ordering},
+		mpsc::{self, TryRecvError},
+	},
+	thread::JoinHandle,
+	time::{Duration, Instant},
+};
+
+pub static ENTITY_COUNTER: AtomicI32 = AtomicI32::new(1);
+
+const PROTOCOL_VERSION: i32 = 3;
+
+pub const TICKS_PER_SECOND: u32 = 20;
+const TICK_DURATION: Duration =
+	Duration::from_millis(1000 / TICKS_PER_SECOND as u64);
+/// How far behind schedule the loop may fall before it stops trying to
+/// catch up and skips the missed ticks instead.
+const MAX_TICK_BACKLOG: Duration = Duration::from_secs(2);
+/// How often dirty chunks and `level.dat` are written to disk.
+const AUTOSAVE_INTERVAL: i64 = 5 * 60 * TICKS_PER_SECOND as i64;
+/// Number of recent tick durations kept for lag detection.
+const TICK_SAMPLES: usize = 100;
+/// How many chunks each player is sent per tick, so walking into new
+/// terrain does not stall the tick loop.
+const CHUNKS_PER_TICK: usize = 4;
+/// How far from a player, in blocks, they may dig or place blocks.
+const REACH: f64 = 6.0;
+/// The digging status a client sends once it has broken a block.
+const DIG_FINISHED: u8 = 3;
+/// How often a keep-alive is sent to every client, so that clients can tell
+/// a quiet server from a dead connection.
+const KEEP_ALIVE_INTERVAL: i64 = TICKS_PER_SECOND as i64;
+/// How often players are teleported to their exact position on the other
+/// clients, to correct the drift that rounding relative moves accumulates.
+const TELEPORT_INTERVAL: i64 = 400;
+/// How long after a block breaks its drop can be picked up, in ticks.
+const BLOCK_DROP_PICKUP_DELAY: u32 = 10;
+/// How long after a player throws an item anyone can pick it up, in ticks,
+/// so that it does not land straight back in their inventory.
+const PLAYER_DROP_PICKUP_DELAY: u32 = 40;
+/// How long shutting down waits for kick messages to be written before
+/// giving up on slow clients.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Messages sent from connection threads to the server thread.
+pub enum Event {
+	Connected {
+		id: i32,
+		address: SocketAddr,
+		outbound: mpsc::Sender<Vec<ClientboundPacket>>,
+		/// The thread writing to the connection, which exits once
+		/// `outbound` is dropped and everything queued has been written.
+		writer: JoinHandle<()>,
+	},
+	Packet {
+		id: i32,
+		packet: ServerboundPacket,
+	},
+	/// The connection ended, either because the client said why or because
+	/// reading from it failed.
+	Disconnected {
+		id: i32,
+		reason: String,
+	},
+	/// A line typed into the server's console.
+	Console {
+		line: String,
+	},
+	/// The process was asked to terminate.
+	Stop,
+}
+
+/// Per-connection state, owned by the server thread.
+pub struct Session {
+	pub id: i32,
+	pub player: Option<Player>,
+	/// The client's IP address, which `banned-ips.txt` is checked against.
+	address: IpAddr,
+	inbound: Vec<ServerboundPacket>,
+	pending: Vec<ClientboundPacket>,
+	outbound: mpsc::Sender<Vec<ClientboundPacket>>,
+	writer: JoinHandle<()>,
+	/// Chunks the client has been sent and not told to unload since.
+	loaded_chunks: HashSet<(i32, i32)>,
+	/// Other players the client has been sent a spawn packet for.
+	tracked_players: HashSet<i32>,
+	/// Dropped items the client has been sent a spawn packet for.
+	tracked_items: HashSet<i32>,
+	/// Where the other clients last saw this session's player.
+	sent_position: Option<EntityPosition>,
+	/// The hash the client was given in the handshake, which online mode
+	/// asks the session server about.
+	server_hash: String,
+	/// A login waiting on the session server.
+	pending_login: Option<PendingLogin>,
+	/// When the client last sent a packet.
+	last_received: Instant,
+	/// Set once the session should be dropped after its pending packets
+	/// have been flushed.
+	closing: bool,
+}
+
+impl Session {
+	/// Queues a packet to be sent at the end of the current tick.
+	pub fn send(&mut self, packet: ClientboundPacket) {
+		self.pending.push(packet);
+	}
+
+	/// Sends a chunk and remembers that the client has it.
+	fn load_chunk(&mut self, world: &mut World, x: i32, z: i32) {
+		let chunk = world.chunk(x, z);
+		self.send(ClientboundPacket::PreChunk { x, z, load: true });
+		self.send(chunk.to_packet());
+		self.loaded_chunks.insert((x, z));
+	}
+
+	fn unload_chunk(&mut self, x: i32, z: i32) {
+		if self.loaded_chunks.remove(&(x, z)) {
+			self.send(ClientboundPacket::PreChunk { x, z, load: false });
+		}
+	}
+
+	/// Hands the packets queued during this tick to the connection's writer
+	/// thread. A closed queue means the connection is already going away, so
+	/// the packets are dropped.
+	fn flush(&mut self) {
+		if !self.pending.is_empty() {
+			let _ = self.outbound.send(std::mem::take(&mut self.pending));
+		}
+	}
+}
+
+/// What a client sent to log in while the session server is verifying it.
+struct PendingLogin {
+	username: String,
+	dimension: i8,
+}
+
+/// The session server's verdict on a session's login, from the thread that
+/// asked it.
+type Verification = (i32, crate::Result<bool>);
+
+/// A player's position and look as spawn and movement packets encode them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EntityPosition {
+	x: i32,
+	y: i32,
+	z: i32,
+	yaw: i8,
+	pitch: i8,
+}
+
+impl EntityPosition {
+	fn of(player: &Player) -> Self {
+		Self {
+			x: to_fixed_point(player.x),
+			y: to_fixed_point(player.y),
+			z: to_fixed_point(player.z),
+			yaw: to_angle(player.yaw),
+			pitch: to_angle(player.pitch),
+		}
+	}
+
+	/// Returns the packet that moves an entity from `self` to `to` on a
+	/// client, if it moved at all. Steps too large for a relative move, and
+	/// every move when `teleport` is set, become a teleport.
+	fn movement(
+		self,
+		entity_id: i32,
+		to: Self,
+		teleport: bool,
+	) -> Option<ClientboundPacket> {
+		let relative = |from: i32, to: i32| i8::try_from(to - from).ok();
+		let delta = (
+			relative(self.x, to.x),
+			relative(self.y, to.y),
+			relative(self.z, to.z),
+		);
+		let moved = (self.x, self.y, self.z) != (to.x, to.y, to.z);
+		let looked = (self.yaw, self.pitch) != (to.yaw, to.pitch);
+
+		Some(match delta {
+			(Some(dx), Some(dy), Some(dz)) if !teleport => {
+				match (moved, looked) {
+					(true, true) => {
+						ClientboundPacket::EntityLookAndRelativeMove {
+							entity_id,
+							dx,
+							dy,
+							dz,
+							yaw: to.yaw,
+							pitch: to.pitch,
+						}
+					}
+					(true, false) => ClientboundPacket::EntityRelativeMove {
+						entity_id,
+						dx,
+						dy,
+						dz,
+					},
+					(false, true) => ClientboundPacket::EntityLook {
+						entity_id,
+						yaw: to.yaw,
+						pitch: to.pitch,
+					},
+					(false, false) => return None,
+				}
+			}
+			_ => ClientboundPacket::EntityTeleport {
+				entity_id,
+				x: to.x,
+				y: to.y,
+				z: to.z,
+				yaw: to.yaw,
+				pitch: to.pitch,
+			},
+		})
+	}
+}
+
+/// Rolling window of recent tick durations.
+struct TickTimes {
+	samples: [Duration; TICK_SAMPLES],
+	index: usize,
+}
+
+impl Default for TickTimes {
+	fn default() -> Self {
+		Self {
+			samples: [Duration::ZERO; TICK_SAMPLES],
+			index: 0,
+		}
+	}
+}
+
+impl TickTimes {
+	fn record(&mut self, duration: Duration) {
+		self.samples[self.index % TICK_SAMPLES] = duration;
+		self.index += 1;
+	}
+
+	fn mean(&self) -> Duration {
+		let count = self.index.clamp(1, TICK_SAMPLES);
+		self.samples[..count].iter().sum::<Duration>() / count as u32
+	}
+}
+
+/// The server-wide registry of sessions and the world they play in.
+///
+/// Only the server thread touches it; connection threads talk to it through
+/// [`Event`]s, which are queued per session and processed once per tick.
+pub struct Server {
+	config: Config,
+	sessions: HashMap<i32, Session>,
+	world: World,
+	commands: Commands,
+	lists: Lists,
+	/// Dropped items, by entity id.
+	items: HashMap<i32, ItemEntity>,
+	random: JavaRandom,
+	/// Handed to the threads that ask the session server about logins.
+	verifier: mpsc::Sender<Verification>,
+	verifications: mpsc::Receiver<Verification>,
+	tick_times: TickTimes,
+	/// Set by [`Server::stop`] to end the main loop after the current tick.
+	stopping: bool,
+}
+
+impl Server {
+	pub fn new(world: World, config: Config, lists: Lists) -> Self {
+		let (verifier, verifications) = mpsc::channel();
+
+		Self {
+			config,
+			sessions: HashMap::new(),
+			world,
+			commands: Commands::with_builtins(),
+			lists,
+			items: HashMap::new(),
+			random: JavaRandom::new(
+				RandomState::new().build_hasher().finish() as i64
+			),
+			verifier,
+			verifications,
+			tick_times: TickTimes::default(),
+			stopping: false,
+		}
+	}
+
+	pub fn commands(&self) -> &Commands {
+		&self.commands
+	}
+
+	pub fn lists(&self) -> &Lists {
+		&self.lists
+	}
+
+	pub fn lists_mut(&mut self) -> &mut Lists {
+		&mut self.lists
+	}
+
+	/// Whether the session belongs to a player listed in `ops.txt`.
+	pub fn is_op(&self, id: i32) -> bool {
+		self.player(id)
+			.is_some_and(|player| self.lists.ops.contains(&player.username))
+	}
+
+	/// Whether a command sender may run commands that need `permission`.
+	pub fn has_permission(
+		&self,
+		sender: Sender,
+		permission: Permission,
+	) -> bool {
+		match (sender, permission) {
+			(_, Permission::Everyone) | (Sender::Console, _) => true,
+			(Sender::Player(id), Permission::Operator) => self.is_op(id),
+		}
+	}
+
+	/// The name a command sender goes by in messages.
+	pub fn sender_name(&self, sender: Sender) -> &str {
+		match sender {
+			Sender::Player(id) => self.username(id),
+			Sender::Console => "Server",
+		}
+	}
+
+	/// Sends a line to a command sender: as chat to a player, or to the log
+	/// for the console.
+	pub fn reply(&mut self, sender: Sender, message: impl Into<String>) {
+		match sender {
+			Sender::Player(id) => self.send_message(id, message),
+			Sender::Console => tracing::info!("{}", message.into()),
+		}
+	}
+
+	/// Returns the player of a session once they have logged in, unless
+	/// the session is being closed.
+	pub fn player(&self, id: i32) -> Option<&Player> {
+		self.sessions
+			.get(&id)
+			.filter(|session| !session.closing)
+			.and_then(|session| session.player.as_ref())
+			.filter(|player| player.logged_in)
+	}
+
+	fn player_mut(&mut self, id: i32) -> Option<&mut Player> {
+		self.sessions
+			.get_mut(&id)
+			.filter(|session| !session.closing)
+			.and_then(|session| session.player.as_mut())
+			.filter(|player| player.logged_in)
+	}
+
+	pub fn username(&self, id: i32) -> &str {
+		self.player(id)
+			.map_or("", |player| player.username.as_str())
+	}
+
+	/// Iterates over the logged in players and their session ids.
+	pub fn players(&self) -> impl Iterator<Item = (i32, &Player)> {
+		self.sessions
+			.keys()
+			.filter_map(|id| Some((*id, self.player(*id)?)))
+	}
+
+	/// Finds the session of a logged in player by name, ignoring case.
+	pub fn find_player(&self, username: &str) -> Option<i32> {
+		self.players()
+			.find(|(_, player)| player.username.eq_ignore_ascii_case(username))
+			.map(|(id, _)| id)
+	}
+
+	/// Returns the IP address of a session's client.
+	pub fn address(&self, id: i32) -> Option<IpAddr> {
+		self.sessions.get(&id).map(|session| session.address)
+	}
+
+	/// Iterates over the ids of the sessions connected from an address,
+	/// whether or not they have logged in.
+	pub fn sessions_from(
+		&self,
+		address: IpAddr,
+	) -> impl Iterator<Item = i32> + '_ {
+		self.sessions
+			.values()
+			.filter(move |session| session.address == address)
+			.map(|session| session.id)
+	}
+
+	/// Sends a chat line to a single session.
+	pub fn send_message(&mut self, id: i32, message: impl Into<String>) {
+		if let Some(session) = self.sessions.get_mut(&id) {
+			session.send(ClientboundPacket::ChatMessage {
+				message: message.into(),
+			});
+		}
+	}
+
+	/// Sends a chat line to every logged in player.
+	pub fn broadcast_message(&mut self, message: &str) {
+		for session in self.sessions.values_mut() {
+			if !session.closing
+				&& session
+					.player
+					.as_ref()
+					.is_some_and(|player| player.logged_in)
+			{
+				session.send(ClientboundPacket::ChatMessage {
+					message: message.into(),
+				});
+			}
+		}
+	}
+
+	pub fn time(&self) -> i64 {
+		self.world.time
+	}
+
+	/// Changes the time of day and tells every client right away.
+	pub fn set_time(&mut self, time: i64) {
+		self.world.time = time;
+		for session in self.sessions.values_mut() {
+			if session.player.is_some() {
+				session.send(ClientboundPacket::TimeUpdate { time });
+			}
+		}
+	}
+
+	/// Moves a player, sending them the chunk they land in first so that
+	/// they do not fall through unloaded ground.
+	pub fn teleport(&mut self, id: i32, (x, y, z): (f64, f64, f64)) {
+		let Some(session) = self.sessions.get_mut(&id) else {
+			return;
+		};
+		let Some(player) = &mut session.player else {
+			return;
+		};
+		player.x = x;
+		player.y = y;
+		player.z = z;
+		player.stance = y + 1.62;
+		player.health.fall_distance = 0.0;
+		let packet = ClientboundPacket::PlayerPositionAndLook {
+			x,
+			stance: player.stance,
+			y,
+			z,
+			yaw: player.yaw,
+			pitch: player.pitch,
+			on_ground: player.on_ground,
+		};
+
+		let (chunk_x, chunk_z) = player.chunk_position();
+		if !session.loaded_chunks.contains(&(chunk_x, chunk_z)) {
+			session.load_chunk(&mut self.world, chunk_x, chunk_z);
+		}
+		session.send(packet);
+	}
+
+	/// Writes every player's file and the world to disk, then unloads the
+	/// chunks no one has loaded. Players who are being kicked are saved too,
+	/// since they are still in the world.
+	pub fn save(&mut self) -> crate::Result<()> {
+		let players = self
+			.sessions
+			.values()
+			.filter_map(|session| session.player.as_ref())
+			.filter(|player| player.logged_in);
+		for player in players {
+			self.world.save_player(player)?;
+		}
+		self.world.save()?;
+
+		// Only chunks someone can see have to stay in memory.
+		let in_use = self
+			.sessions
+			.values()
+			.flat_map(|session| session.loaded_chunks.iter().copied())
+			.collect();
+		let unloaded = self.world.unload_chunks(&in_use);
+		tracing::debug!("Unloaded {unloaded} chunks");
+
+		Ok(())
+	}
+
+	/// Shuts the server down once the current tick is over: everyone is
+	/// kicked and the world is saved before [`Server::run`] returns.
+	pub fn stop(&mut self) {
+		self.stopping = true;
+	}
+
+	/// Runs the main loop at [`TICKS_PER_SECOND`] until the server is
+	/// stopped, or every connection thread (and the accept loop) has gone
+	/// away. Either way, everyone is kicked and saved before it returns.
+	pub fn run(mut self, events: mpsc::Receiver<Event>) {
+		let mut next_tick = Instant::now();
+
+		loop {
+			let started = Instant::now();
+
+			loop {
+				match events.try_recv() {
+					Ok(event) => self.handle_event(event),
+					Err(TryRecvError::Empty) => break,
+					Err(TryRecvError::Disconnected) => {
+						self.stop();
+						break;
+					}
+				}
+			}
+
+			self.tick();
+			self.flush();
+			if self.stopping {
+				// Connections made from now on find the server gone and
+				// are closed right away.
+				drop(events);
+				self.shut_down();
+				return;
+			}
+
+			let elapsed = started.elapsed();
+			self.tick_times.record(elapsed);
+			if elapsed > TICK_DURATION {
+				tracing::debug!("tick took {}ms", elapsed.as_millis());
+			}
+			if self.world.time % TICK_SAMPLES as i64 == 0
+				&& self.tick_times.mean() > TICK_DURATION
+			{
+				tracing::warn!(
+					"Can't keep up! Mean tick time is {}ms",
+					self.tick_times.mean().as_millis()
+				);
+			}
+
+			next_tick += TICK_DURATION;
+			let now = Instant::now();
+			if next_tick > now {
+				std::thread::sleep(next_tick - now);
+			} else if now - next_tick > MAX_TICK_BACKLOG {
+				let skipped =
+					(now - next_tick).as_millis() / TICK_DURATION.as_millis();
+				tracing::warn!("Running behind, skipping {skipped} ticks");
+				next_tick = now;
+			}
+		}
+	}
+
+	fn handle_event(&mut self, event: Event) {
+		match event {
+			Event::Connected {
+				id,
+				address,
+				outbound,
+				writer,
+			} => {
+				tracing::debug!("session {id} connected from {address}");
+				self.sessions.insert(
+					id,
+					Session {
+						id,
+						player: None,
+						address: address.ip().to_canonical(),
+						inbound: Vec::new(),
+						pending: Vec::new(),
+						outbound,
+						writer,
+						loaded_chunks: HashSet::new(),
+						tracked_players: HashSet::new(),
+						tracked_items: HashSet::new(),
+						sent_position: None,
+						server_hash: String::new(),
+						pending_login: None,
+						last_received: Instant::now(),
+						closing: false,
+					},
+				);
+			}
+			Event::Packet { id, packet } => {
+				if let Some(session) = self.sessions.get_mut(&id) {
+					session.last_received = Instant::now();
+					session.inbound.push(packet);
+				}
+			}
+			Event::Disconnected { id, reason } => {
+				match self.player(id) {
+					Some(player) => tracing::info!(
+						"{} lost connection: {reason}",
+						player.username
+					),
+					None => {
+						tracing::debug!("session {id} disconnected: {reason}")
+					}
+				}
+				self.remove_session(id);
+			}
+			Event::Stop => self.stop(),
+			Event::Console { line } => {
+				let line = line.trim();
+				if !line.is_empty() {
+					// Slashes are optional on the console.
+					self.run_command(
+						Sender::Console,
+						line.trim_start_matches('/'),
+					);
+				}
+			}
+		}
+	}
+
+	fn tick(&mut self) {
+		self.complete_verifications();
+
+		let ids: Vec<i32> = self.sessions.keys().copied().collect();
+		for id in ids {
+			let Some(session) = self.sessions.get_mut(&id) else {
+				continue;
+			};
+
+			for packet in std::mem::take(&mut session.inbound) {
+				self.handle_packet(id, packet);
+			}
+		}
+
+		self.reap_idle_sessions();
+		self.stream_chunks();
+		self.track_players();
+		self.tick_health();
+		self.world.tick();
+		self.tick_items();
+		self.track_items();
+
+		if self.world.time % AUTOSAVE_INTERVAL == 0 {
+			if let Err(err) = self.save() {
+				tracing::error!("Failed to save the world: {err}");
+			}
+		}
+
+		if self.world.time % KEEP_ALIVE_INTERVAL == 0 {
+			for session in self.sessions.values_mut() {
+				session.send(ClientboundPacket::KeepAlive);
+			}
+		}
+
+		if self.world.time % TICKS_PER_SECOND as i64 == 0 {
+			let time = self.world.time;
+			for session in self.sessions.values_mut() {
+				if session.player.is_some() {
+					session.send(ClientboundPacket::TimeUpdate { time });
+				}
+			}
+		}
+	}
+
+	/// Kicks everyone, saves the world and waits a little for the kick
+	/// messages to reach the clients.
+	fn shut_down(&mut self) {
+		tracing::info!("Stopping the server");
+		let ids: Vec<_> = self.sessions.keys().copied().collect();
+		for id in ids {
+			self.kick(id, "Server closed");
+		}
+
+		tracing::info!("Saving the world");
+		if let Err(err) = self.save() {
+			tracing::error!("Failed to save the world: {err}");
+		}
+
+		// Everyone is leaving, so there is nobody to tell who left.
+		let writers: Vec<_> = self
+			.sessions
+			.drain()
+			.map(|(_, mut session)| {
+				session.flush();
+				session.writer
+			})
+			.collect();
+
+		let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+		while writers.iter().any(|writer| !writer.is_finished())
+			&& Instant::now() < deadline
+		{
+			std::thread::sleep(Duration::from_millis(10));
+		}
+	}
+
+	/// Kicks every session that has not sent anything within the configured
+	/// timeout. Dropping the session also closes its socket, which frees the
+	/// reader thread blocked on it.
+	fn reap_idle_sessions(&mut self) {
+		let idle: Vec<_> = self
+			.sessions
+			.values()
+			.filter(|session| {
+				!session.closing
+					&& session.last_received.elapsed() > self.config.timeout
+			})
+			.map(|session| session.id)
+			.collect();
+		for id in idle {
+			self.kick(id, "Timed out");
+		}
+	}
+
+	/// Sends each player the chunks within the view distance of the chunk
+	/// they stand in, nearest first, and unloads the ones they left behind.
+	fn stream_chunks(&mut self) {
+		let radius = self.config.view_distance as i32;
+
+		for session in self.sessions.values_mut() {
+			let Some(player) =
+				session.player.as_ref().filter(|player| player.logged_in)
+			else {
+				continue;
+			};
+			let (center_x, center_z) = player.chunk_position();
+			let in_range = |(x, z): (i32, i32)| {
+				(x - center_x).abs() <= radius && (z - center_z).abs() <= radius
+			};
+
+			let left: Vec<_> = session
+				.loaded_chunks
+				.iter()
+				.copied()
+				.filter(|chunk| !in_range(*chunk))
+				.collect();
+			for (x, z) in left {
+				session.unload_chunk(x, z);
+			}
+
+			let mut missing: Vec<_> = (center_x - radius..=center_x + radius)
+				.flat_map(|x| {
+					(center_z - radius..=center_z + radius).map(move |z| (x, z))
+				})
+				.filter(|chunk| !session.loaded_chunks.contains(chunk))
+				.collect();
+			missing.sort_by_key(|(x, z)| {
+				(x - center_x).pow(2) + (z - center_z).pow(2)
+			});
+			for (x, z) in missing.into_iter().take(CHUNKS_PER_TICK) {
+				session.load_chunk(&mut self.world, x, z);
+			}
+		}
+	}
+
+	/// Logs a player in once they have been let in: sends them the world
+	/// around the spawn point and announces them to everyone else.
+	fn log_in(&mut self, id: i32, username: String, dimension: i8) {
+		if let Some(older) = self.find_player(&username) {
+			self.kick(older, "You logged in from another location");
+		}
+		if self.players().count() >= self.config.max_players as usize {
+			self.kick(id, "The server is full!");
+			return;
+		}
+		let Some(session) = self.sessions.get_mut(&id) else {
+			return;
+		};
+
+		let map_seed = self.world.seed();
+		let (spawn_x, spawn_y, spawn_z) = self.world.spawn();
+		tracing::debug!("dimension: {}", dimension);
+
+		// Send login response packet
+		session.send(ClientboundPacket::Login {
+			entity_id: session.id,
+			// Unused by the client
+			server_name: String::new(),
+			motd: self.config.motd.clone(),
+			map_seed,
+			dimension,
+		});
+
+		let mut player = Player {
+			username,
+			logged_in: false,
+			x: spawn_x as f64 + 0.5,
+			y: spawn_y as f64,
+			z: spawn_z as f64 + 0.5,
+			yaw: 0.0,
+			pitch: 0.0,
+			stance: spawn_y as f64 + 1.62,
+			on_ground: true,
+			inventory: Inventory::default(),
+			held_item: 0,
+			health: Health::default(),
+		};
+		if let Err(err) = self.world.load_player(&mut player) {
+			tracing::error!("Failed to load {}: {err}", player.username);
+		}
+		// Clients start out with the first hotbar slot selected and only
+		// send HOLDING_CHANGE once they select another.
+		player.held_item =
+			player.inventory.hotbar()[0].map_or(0, |stack| stack.id);
+
+		// The client needs the ground under its feet before it is
+		// placed; the rest of the view is streamed over the next
+		// ticks.
+		let (chunk_x, chunk_z) = player.chunk_position();
+		session.load_chunk(&mut self.world, chunk_x, chunk_z);
+		tracing::debug!("Wrote map data");
+
+		// Write spawn position packet
+		session.send(ClientboundPacket::SpawnPosition {
+			x: spawn_x,
+			y: spawn_y,
+			z: spawn_z,
+		});
+
+		tracing::debug!("Wrote spawn position");
+
+		for section in Section::ALL {
+			session.send(player.inventory.to_packet(section));
+		}
+		session.send(player.health.to_packet());
+
+		// Write position and look packet
+		session.send(ClientboundPacket::PlayerPositionAndLook {
+			x: player.x,
+			stance: player.stance,
+			y: player.y,
+			z: player.z,
+			yaw: player.yaw,
+			pitch: player.pitch,
+			on_ground: player.on_ground,
+		});
+
+		tracing::debug!("Wrote player rotation and position");
+
+		session.send(ClientboundPacket::TimeUpdate {
+			time: self.world.time,
+		});
+		player.logged_in = true;
+		let message = format!("{} joined the game.", player.username);
+		tracing::info!("{} logged in", player.username);
+		session.player = Some(player);
+		self.broadcast_message(&message);
+	}
+
+	/// Why a player may not log in under `username`, if they may not.
+	fn login_refusal(&self, username: &str) -> Option<&'static str> {
+		if self.lists.banned_players.contains(username) {
+			Some("You are banned from this server!")
+		} else if self.config.white_list
+			&& !self.lists.whitelist.contains(username)
+		{
+			Some("You are not white-listed on this server!")
+		} else {
+			None
+		}
+	}
+
+	/// Finishes the logins the session server has answered for.
+	fn complete_verifications(&mut self) {
+		while let Ok((id, verified)) = self.verifications.try_recv() {
+			let Some(login) = self
+				.sessions
+				.get_mut(&id)
+				.and_then(|session| session.pending_login.take())
+			else {
+				continue;
+			};
+
+			match verified {
+				Ok(true) => self.log_in(id, login.username, login.dimension),
+				Ok(false) => {
+					tracing::info!("{} failed to verify", login.username);
+					self.kick(id, "Failed to verify username!");
+				}
+				Err(err) => {
+					tracing::error!(
+						"Failed to verify {}: {err}",
+						login.username
+					);
+					self.kick(id, "Failed to verify username!");
+				}
+			}
+		}
+	}
+
+	/// Runs a command, replying to whoever sent it with the error if it
+	/// fails.
+	fn run_command(&mut self, sender: Sender, input: &str) {
+		let (name, input) = input.split_once(' ').unwrap_or((input, ""));
+		let operator = self.has_permission(sender, Permission::Operator);
+		let result = self.commands.find(name, operator).and_then(|command| {
+			(command.run)(self, sender, Arguments::new(&command, input))
+		});
+
+		if let Err(err) = result {
+			self.reply(sender, err.to_string());
+		}
+	}
+
+	/// Breaks the block a player has finished digging, dropping whatever
+	/// the block leaves behind for the tool in their hand.
+	fn dig(&mut self, id: i32, x: i32, y: i32, z: i32) {
+		if !self.can_reach(id, x, y, z) || self.is_protected(id, x, z) {
+			self.resend_block(id, x, y, z);
+			return;
+		}
+		let block = blocks::get(self.world.block(x, y, z).0)
+			.filter(|block| !block.replaceable && block.hardness >= 0.0);
+		let Some(block) = block else {
+			self.resend_block(id, x, y, z);
+			return;
+		};
+
+		self.world.set_block(x, y, z, blocks::AIR, 0);
+		self.broadcast_block(x, y, z);
+
+		let tool = self
+			.player(id)
+			.and_then(|player| items::get(player.held_item))
+			.and_then(|item| item.tool);
+		let Some(item) = block.drops_for(tool, &mut self.random) else {
+			return;
+		};
+
+		// Scattered within the block, the way the Alpha server drops them.
+		let mut offset = || self.random.next_float() as f64 * 0.7 + 0.15;
+		let (x, y, z) = (
+			x as f64 + offset(),
+			y as f64 + offset(),
+			z as f64 + offset(),
+		);
+		let velocity = (
+			self.random.next_double() * 0.2 - 0.1,
+			0.2,
+			self.random.next_double() * 0.2 - 0.1,
+		);
+		self.spawn_item(item, (x, y, z), velocity, BLOCK_DROP_PICKUP_DELAY);
+	}
+
+	/// Hurts players who are drowning, in lava or below the world.
+	fn tick_health(&mut self) {
+		let players: Vec<_> = self
+			.players()
+			.filter(|(_, player)| !player.health.is_dead())
+			.map(|(id, player)| (id, (player.x, player.y, player.z)))
+			.collect();
+		for (id, (x, y, z)) in players {
+			let (block_x, block_z) = (math::floor(x), math::floor(z));
+			let feet = self.world.block(block_x, math::floor(y), block_z).0;
+			let head =
+				self.world.block(block_x, math::floor(y + 1.62), block_z).0;
+			let is_water =
+				|block| matches!(block, blocks::FLOWING_WATER | blocks::WATER);
+			let is_lava =
+				|block| matches!(block, blocks::FLOWING_LAVA | blocks::LAVA);
+			let surroundings = Surroundings {
+				in_water: is_water(feet) || is_water(head),
+				head_in_water: is_water(head),
+				in_lava: is_lava(feet) || is_lava(head),
+				in_void: y < -64.0,
+			};
+
+			let hurt = self
+				.player_mut(id)
+				.is_some_and(|player| player.health.tick(surroundings));
+			if hurt {
+				self.health_changed(id);
+			}
+		}
+	}
+
+	/// Tells a player how healthy they are after they were hurt, and kills
+	/// them if they have no health left.
+	fn health_changed(&mut self, id: i32) {
+		let Some(session) = self.sessions.get_mut(&id) else {
+			return;
+		};
+		let Some(player) = &session.player else {
+			return;
+		};
+		let dead = player.health.is_dead();
+		let packet = player.health.to_packet();
+		session.send(packet);
+		if dead {
+			self.die(id);
+		}
+	}
+
+	/// Spills everything a dead player carried around where they died.
+	/// They stay dead until their client asks to respawn.
+	fn die(&mut self, id: i32) {
+		let Some(player) = self.player_mut(id) else {
+			return;
+		};
+		let items = player.inventory.take_all();
+		player.held_item = 0;
+		let position = (player.x, player.y + 1.3, player.z);
+		tracing::info!("{} died", player.username);
+
+		for item in items {
+			// Flung in a random direction, as the Alpha server does.
+			let speed = self.random.next_float() as f64 * 0.5;
+			let angle = self.random.next_float() as f64 * std::f64::consts::TAU;
+			let velocity = (-angle.sin() * speed, 0.2, angle.cos() * speed);
+			self.spawn_item(item, position, velocity, PLAYER_DROP_PICKUP_DELAY);
+		}
+		for section in Section::ALL {
+			self.send_inventory(id, section);
+		}
+	}
+
+	/// Brings a dead player back at the world spawn with full health.
+	fn respawn(&mut self, id: i32) {
+		let (x, y, z) = self.world.spawn();
+		let Some(player) =
+			self.player_mut(id).filter(|player| player.health.is_dead())
+		else {
+			return;
+		};
+		player.health = Health::default();
+		let packet = player.health.to_packet();
+		if let Some(session) = self.sessions.get_mut(&id) {
+			session.send(ClientboundPacket::Respawn);
+			session.send(packet);
+		}
+		self.teleport(id, (x as f64 + 0.5, y as f64, z as f64 + 0.5));
+	}
+
+	/// Drops an item into the world. Clients are told about it by
+	/// [`Server::track_items`].
+	fn spawn_item(
+		&mut self,
+		item: ItemStack,
+		(x, y, z): (f64, f64, f64),
+		(velocity_x, velocity_y, velocity_z): (f64, f64, f64),
+		pickup_delay: u32,
+	) {
+		let id = ENTITY_COUNTER.fetch_add(1, Ordering::Relaxed);
+		self.items.insert(
+			id,
+			ItemEntity {
+				id,
+				item,
+				x,
+				y,
+				z,
+				velocity_x,
+				velocity_y,
+				velocity_z,
+				age: 0,
+				pickup_delay,
+			},
+		);
+	}
+
+	/// Lets dropped items fall, removes the ones that expired and hands
+	/// the others to players standing close enough to pick them up.
+	fn tick_items(&mut self) {
+		let world = &mut self.world;
+		for item in self.items.values_mut() {
+			item.tick(|x, y, z| blocks::has_collision(world.block(x, y, z).0));
+		}
+		self.items.retain(|_, item| !item.is_expired());
+
+		let ids: Vec<_> = self.items.keys().copied().collect();
+		for item_id in ids {
+			let item = &self.items[&item_id];
+			if !item.can_be_picked_up() {
+				continue;
+			}
+			let collector = self.sessions.values_mut().find_map(|session| {
+				let player = session.player.as_mut().filter(|player| {
+					player.logged_in
+						&& !session.closing
+						&& !player.health.is_dead()
+				})?;
+				(item.is_near(player) && player.inventory.add(item.item))
+					.then_some(session)
+			});
+			let Some(collector) = collector else {
+				continue;
+			};
+			let collector_id = collector.id;
+			collector
+				.send(ClientboundPacket::AddToInventory { item: item.item });
+
+			self.items.remove(&item_id);
+			for session in self.sessions.values_mut() {
+				if session.tracked_items.contains(&item_id) {
+					session.send(ClientboundPacket::CollectItem {
+						collected_entity_id: item_id,
+						collector_entity_id: collector_id,
+					});
+				}
+			}
+		}
+	}
+
+	/// Spawns dropped items for the clients that have their chunk loaded,
+	/// and destroys them for the ones that no longer do or once they are
+	/// gone.
+	fn track_items(&mut self) {
+		for session in self.sessions.values_mut() {
+			let logged_in = session
+				.player
+				.as_ref()
+				.is_some_and(|player| player.logged_in);
+			let gone: Vec<_> = session
+				.tracked_items
+				.iter()
+				.copied()
+				.filter(|id| {
+					!logged_in
+						|| self.items.get(id).is_none_or(|item| {
+							!session
+								.loaded_chunks
+								.contains(&item.chunk_position())
+						})
+				})
+				.collect();
+			for id in gone {
+				session.tracked_items.remove(&id);
+				session
+					.send(ClientboundPacket::DestroyEntity { entity_id: id });
+			}
+			if !logged_in {
+				continue;
+			}
+
+			for item in self.items.values() {
+				if session.loaded_chunks.contains(&item.chunk_position())
+					&& session.tracked_items.insert(item.id)
+				{
+					session.send(item.to_packet());
+				}
+			}
+		}
+	}
+
+	/// Places a block from the player's hotbar unless something other than
+	/// air or liquid is already there or it would end up inside a player.
+	fn place(&mut self, id: i32, block: u8, x: i32, y: i32, z: i32) {
+		// The block has to be what the client last said is in the player's
+		// hand, and the server has to agree that they have one.
+		let held = self.player(id).is_some_and(|player| {
+			player.held_item == block as i16
+				&& player.inventory.in_hotbar(block as i16)
+		});
+		let obstructed = blocks::has_collision(block)
+			&& self
+				.sessions
+				.values()
+				.filter_map(|session| session.player.as_ref())
+				.any(|player| player.intersects_block(x, y, z));
+		let placeable = held
+			&& blocks::get(block).is_some()
+			&& self.can_reach(id, x, y, z)
+			&& !self.is_protected(id, x, z)
+			&& !obstructed
+			&& blocks::is_replaceable(self.world.block(x, y, z).0);
+		if !placeable {
+			// The client takes the block out of its hand as it places it.
+			self.resend_block(id, x, y, z);
+			self.send_inventory(id, Section::Main);
+			return;
+		}
+
+		if let Some(player) = self.player_mut(id) {
+			player.inventory.take_from_hotbar(block as i16);
+		}
+		self.world.set_block(x, y, z, block, 0);
+		self.broadcast_block(x, y, z);
+	}
+
+	/// Sets a section of the player's inventory on their client to what the
+	/// server thinks it holds.
+	fn send_inventory(&mut self, id: i32, section: Section) {
+		let Some(session) = self.sessions.get_mut(&id) else {
+			return;
+		};
+		if let Some(player) = &session.player {
+			let packet = player.inventory.to_packet(section);
+			session.send(packet);
+		}
+	}
+
+	/// Whether the session's player is close enough to the block to touch
+	/// it and the block lies inside the world. Checked before anything else
+	/// so that clients cannot make the server load far away chunks.
+	fn can_reach(&self, id: i32, x: i32, y: i32, z: i32) -> bool {
+		let Some(player) = self
+			.sessions
+			.get(&id)
+			.and_then(|session| session.player.as_ref())
+			.filter(|player| player.logged_in)
+		else {
+			return false;
+		};
+		let (dx, dy, dz) = (
+			player.x - (x as f64 + 0.5),
+			player.y + 1.5 - (y as f64 + 0.5),
+			player.z - (z as f64 + 0.5),
+		);
+
+		(0..CHUNK_HEIGHT as i32).contains(&y)
+			&& dx * dx + dy * dy + dz * dz <= REACH * REACH
+	}
+
+	/// Whether the block column lies within the spawn protection radius and
+	/// the session's player is not an operator, who may change it anyway.
+	fn is_protected(&self, id: i32, x: i32, z: i32) -> bool {
+		let radius = self.config.spawn_protection as i32;
+		let (spawn_x, _, spawn_z) = self.world.spawn();
+		let distance = (x - spawn_x).abs().max((z - spawn_z).abs());
+
+		radius > 0 && distance <= radius && !self.is_op(id)
+	}
+
+	/// Tells one client the actual state of a block it changed on its own,
+	/// undoing a dig or placement the server refused.
+	fn resend_block(&mut self, id: i32, x: i32, y: i32, z: i32) {
+		let loaded = self.sessions.get(&id).is_some_and(|session| {
+			session.loaded_chunks.contains(&(x >> 4, z >> 4))
+		});
+		if !loaded || !(0..CHUNK_HEIGHT as i32).contains(&y) {
+			return;
+		}
+
+		let packet = self.block_change(x, y, z);
+		if let Some(session) = self.sessions.get_mut(&id) {
+			session.send(packet);
+		}
+	}
+
+	/// Sends the block at the given coordinates to every client that has its
+	/// chunk loaded.
+	fn broadcast_block(&mut self, x: i32, y: i32, z: i32) {
+		let packet = self.block_change(x, y, z);
+		for session in self.sessions.values_mut() {
+			if session.loaded_chunks.contains(&(x >> 4, z >> 4)) {
+				session.send(packet.clone());
+			}
+		}
+	}
+
+	fn block_change(&mut self, x: i32, y: i32, z: i32) -> ClientboundPacket {
+		let (block_type, metadata) = self.world.block(x, y, z);
+
+		ClientboundPacket::BlockChange {
+			x,
+			y: y as i8,
+			z,
+			block_type,
+			metadata,
+		}
+	}
+
+	/// Relays how players moved to the clients that can see them, then
+	/// spawns players for the clients that have their chunk loaded and
+	/// destroys them for the ones that no longer do.
+	fn track_players(&mut self) {
+		let teleport = self.world.time % TELEPORT_INTERVAL == 0;
+
+		let mut moves = Vec::new();
+		for session in self.sessions.values_mut() {
+			let Some(player) =
+				session.player.as_ref().filter(|player| player.logged_in)
+			else {
+				continue;
+			};
+			let position = EntityPosition::of(player);
+			if let Some(sent) = session.sent_position.replace(position) {
+				let packet = sent.movement(session.id, position, teleport);
+				moves.extend(packet.map(|packet| (session.id, packet)));
+			}
+		}
+		for (id, packet) in moves {
+			for session in self.sessions.values_mut() {
+				if session.tracked_players.contains(&id) {
+					session.send(packet.clone());
+				}
+			}
+		}
+
+		let spawns: Vec<_> = self
+			.players()
+			.map(|(id, player)| {
+				let position = EntityPosition::of(player);
+				let spawn = ClientboundPacket::NamedEntitySpawn {
+					entity_id: id,
+					name: player.username.clone(),
+					x: position.x,
+					y: position.y,
+					z: position.z,
+					rotation: position.yaw,
+					pitch: position.pitch,
+					current_item: player.held_item,
+				};
+				(id, player.chunk_position(), spawn)
+			})
+			.collect();
+		for session in self.sessions.values_mut() {
+			let logged_in = session
+				.player
+				.as_ref()
+				.is_some_and(|player| player.logged_in);
+			for (id, chunk, spawn) in &spawns {
+				if *id == session.id {
+					continue;
+				}
+				let visible =
+					logged_in && session.loaded_chunks.contains(chunk);
+				let tracked = session.tracked_players.contains(id);
+				if visible && !tracked {
+					session.tracked_players.insert(*id);
+					session.send(spawn.clone());
+				} else if !visible && tracked {
+					session.tracked_players.remove(id);
+					session.send(ClientboundPacket::DestroyEntity {
+						entity_id: *id,
+					});
+				}
+			}
+		}
+	}
+
+	/// Disconnects a player, showing them `reason`. The session is dropped
+	/// once the packets queued for it in this tick have been sent.
+	pub fn kick(&mut self, id: i32, reason: &str) {
+		let Some(session) = self.sessions.get_mut(&id) else {
+			return;
+		};
+		if let Some(player) = &session.player {
+			tracing::info!("Kicking {}: {reason}", player.username);
+		}
+		session.send(ClientboundPacket::KickOrDisconnect {
+			reason: reason.into(),
+		});
+		session.closing = true;
+	}
+
+	/// Sends every packet queued during the tick and drops sessions that
+	/// were closed.
+	fn flush(&mut self) {
+		let closed: Vec<_> = self
+			.sessions
+			.values()
+			.filter(|session| session.closing)
+			.map(|session| session.id)
+			.collect();
+		for id in closed {
+			self.remove_session(id);
+		}
+
+		for session in self.sessions.values_mut() {
+			session.flush();
+		}
+	}
+
+	/// Drops a session after sending what was queued for it, and removes its
+	/// player from the other clients. Dropping the outbound queue makes the
+	/// writer thread close the socket.
+	fn remove_session(&mut self, id: i32) {
+		let Some(mut session) = self.sessions.remove(&id) else {
+			return;
+		};
+		session.flush();
+
+		for other in self.sessions.values_mut() {
+			if other.tracked_players.remove(&id) {
+				other.send(ClientboundPacket::DestroyEntity { entity_id: id });
+			}
+		}
+		if let Some(player) = session.player.filter(|player| player.logged_in) {
+			if let Err(err) = self.world.save_player(&player) {
+				tracing::error!("Failed to save {}: {err}", player.username);
+			}
+			self.broadcast_message(&format!(
+				"{} left the game.",
+				player.username
+			));
+		}
+	}
+
+	fn handle_packet(&mut self, id: i32, packet: ServerboundPacket) {
+		let Some(session) = self.sessions.get_mut(&id) else {
+			return;
+		};
+		if session.closing {
+			return;
+		}
+
+		match packet {
+			ServerboundPacket::KeepAlive => {}
+			ServerboundPacket::Handshake { username } => {
+				if self.lists.banned_ips.contains(&session.address.to_string())
+				{
+					tracing::info!(
+						"Refused {username} from banned address {}",
+						session.address
+					);
+					self.kick(
+						id,
+						"Your IP address is banned from this server!",
+					);
+					return;
+				}
+				// A hash of "-" tells the client not to contact the session
+				// server.
+				session.server_hash = if self.config.online_mode {
+					format!("{:x}", RandomState::new().build_hasher().finish())
+				} else {
+					"-".into()
+				};
+				session.send(ClientboundPacket::Handshake {
+					connection_hash: session.server_hash.clone(),
+				});
+				tracing::debug!("username: {username:?}");
+			}
+			ServerboundPacket::Login {
+				protocol_version,
+				username,
+				map_seed,
+				dimension,
+				..
+			} => {
+				tracing::debug!("protocol version: {}", protocol_version);
+				if protocol_version != PROTOCOL_VERSION {
+					self.kick(id, "Outdated client!");
+					return;
+				}
+
+				if session.player.is_some() || session.pending_login.is_some() {
+					return;
+				}
+				tracing::debug!("map seed: {}", map_seed);
+
+				if let Some(reason) = self.login_refusal(&username) {
+					tracing::info!("Refused {username}: {reason}");
+					self.kick(id, reason);
+					return;
+				}
+				let Some(session) = self.sessions.get_mut(&id) else {
+					return;
+				};
+
+				if !self.config.online_mode {
+					self.log_in(id, username, dimension);
+					return;
+				}
+
+				// The session server can only vouch for a login to the hash
+				// this session was given in its handshake.
+				if session.server_hash.is_empty() {
+					self.kick(id, "Failed to verify username!");
+					return;
+				}
+				let server_hash = session.server_hash.clone();
+				session.pending_login = Some(PendingLogin {
+					username: username.clone(),
+					dimension,
+				});
+				let session_server = self.config.session_server.clone();
+				let verifier = self.verifier.clone();
+				std::thread::spawn(move || {
+					let verified =
+						session_server.check(&username, &server_hash);
+					let _ = verifier.send((id, verified));
+				});
+			}
+			ServerboundPacket::PlayerPositionAndLook {
+				x,
+				y,
+				stance,
+				z,
+				yaw,
+				pitch,
+				on_ground,
+			} => {
+				if let Some(player) = &mut session.player {
+					let dy = y - player.y;
+					player.x = x;
+					player.stance = stance;
+					player.y = y;
+					player.z = z;
+					player.yaw = yaw;
+					player.pitch = pitch;
+					player.on_ground = on_ground;
+					if player.health.fall(dy, on_ground) {
+						self.health_changed(id);
+					}
+				}
+			}
+			ServerboundPacket::PlayerPosition {
+				x,
+				y,
+				stance,
+				z,
+				on_ground,
+			} => {
+				if let Some(player) = &mut session.player {
+					let dy = y - player.y;
+					player.x = x;
+					player.y = y;
+					player.stance = stance;
+					player.z = z;
+					player.on_ground = on_ground;
+					if player.health.fall(dy, on_ground) {
+						self.health_changed(id);
+					}
+				}
+			}
+			ServerboundPacket::PlayerLook {
+				yaw,
+				pitch,
+				on_ground,
+			} => {
+				if let Some(player) = &mut session.player {
+					player.yaw = yaw;
+					player.pitch = pitch;
+					player.on_ground = on_ground;
+					if player.health.fall(0.0, on_ground) {
+						self.health_changed(id);
+					}
+				}
+			}
+			ServerboundPacket::ChatMessage { message } => {
+				let Some(player) = self.player(id) else {
+					return;
+				};
+				let message = message.trim();
+				if let Some(command) = message.strip_prefix('/') {
+					tracing::info!(
+						"{} issued server command: /{command}",
+						player.username
+					);
+					self.run_command(Sender::Player(id), command);
+				} else if !message.is_empty() {
+					let message = format!("<{}> {message}", player.username);
+					tracing::info!("{message}");
+					self.broadcast_message(&message);
+				}
+			}
+			ServerboundPacket::PlayerInventory { kind, items } => {
+				let Some(player) = self.player_mut(id) else {
+					return;
+				};
+				if let Err(err) = player.inventory.replace(kind, &items) {
+					tracing::debug!("session {id}: {err}");
+					if let Some(section) = Section::from_kind(kind) {
+						self.send_inventory(id, section);
+					}
+				}
+			}
+			ServerboundPacket::HoldingChange { item_id, .. } => {
+				let Some(player) = self.player_mut(id) else {
+					return;
+				};
+				// Only what is in the hotbar can be taken in hand.
+				player.held_item =
+					if item_id > 0 && player.inventory.in_hotbar(item_id) {
+						item_id
+					} else {
+						0
+					};
+				let item_id = player.held_item;
+				for other in self.sessions.values_mut() {
+					if other.tracked_players.contains(&id) {
+						other.send(ClientboundPacket::HoldingChange {
+							entity_id: id,
+							item_id,
+						});
+					}
+				}
+			}
+			ServerboundPacket::PickupSpawn {
+				item_id,
+				count,
+				rotation,
+				pitch,
+				roll,
+				..
+			} => {
+				// The item leaves from the player's hand rather than from
+				// wherever the client says, and only if they had it.
+				let Some(player) = self.player_mut(id) else {
+					return;
+				};
+				let item = player.inventory.remove(item_id, count);
+				let Some(item) = item.filter(|item| item.count != 0) else {
+					self.send_inventory(id, Section::Main);
+					return;
+				};
+				let position = (player.x, player.y + 1.3, player.z);
+				let velocity = (
+					rotation as f64 / 128.0,
+					pitch as f64 / 128.0,
+					roll as f64 / 128.0,
+				);
+				self.spawn_item(
+					item,
+					position,
+					velocity,
+					PLAYER_DROP_PICKUP_DELAY,
+				);
+			}
+			ServerboundPacket::Player { on_ground } => {
+				if let Some(player) = &mut session.player {
+					player.on_ground = on_ground;
+					if player.health.fall(0.0, on_ground) {
+						self.health_changed(id);
+					}
+				}
+			}
+			ServerboundPacket::Respawn => self.respawn(id),
+			ServerboundPacket::PlayerDigging {
+				status: DIG_FINISHED,
+				x,
+				y,
+				z,
+				..
+			} => self.dig(id, x, y as i32, z),
+			ServerboundPacket::PlayerDigging { .. } => {}
+			ServerboundPacket::PlayerBlockPlacement {
+				item_id,
+				x,
+				y,
+				z,
+				direction,
+			} => {
+				// Negative ids are an empty hand and ids past the block range
+				// are items, neither of which places anything.
+				let Ok(block) = u8::try_from(item_id) else {
+					return;
+				};
+				let (x, y, z) = (x, y as i32, z);
+				let (x, y, z) = match direction {
+					0 => (x, y - 1, z),
+					1 => (x, y + 1, z),
+					2 => (x, y, z - 1),
+					3 => (x, y, z + 1),
+					4 => (x - 1, y, z),
+					5 => (x + 1, y, z),
+					_ => return,
+				};
+				self.place(id, block, x, y, z);
+			}
+			packet => {
+				tracing::error!("unhandled packet: {:#04x}", packet.id());
+			}
+		}
+	}
+}
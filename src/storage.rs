@@ -0,0 +1,194 @@
+use crate::{InvalidPlayerNameSnafu, MisplacedChunkSnafu, Result};
+use oxidized_alpha::{
+	level::LevelData,
+	nbt::{self, Compression},
+	Chunk, Player,
+};
+use snafu::ensure;
+use std::{
+	fs,
+	io::ErrorKind,
+	path::{Path, PathBuf},
+};
+
+const LEVEL_FILE: &str = "level.dat";
+
+/// Reads and writes a world directory in the Alpha on-disk layout.
+///
+/// Chunks live in `<base36(x & 63)>/<base36(z & 63)>/c.<base36(x)>.<base36(z)>.dat`,
+/// players in `players/<username>.dat` and the world metadata in
+/// `level.dat`, all as gzip-compressed NBT.
+pub struct Storage {
+	root: PathBuf,
+}
+
+impl Storage {
+	pub fn open(root: impl AsRef<Path>) -> Result<Self> {
+		let root = root.as_ref().to_path_buf();
+		fs::create_dir_all(&root)?;
+
+		Ok(Self { root })
+	}
+
+	pub fn load_level(&self) -> Result<Option<LevelData>> {
+		let Some(bytes) = read_optional(&self.root.join(LEVEL_FILE))? else {
+			return Ok(None);
+		};
+		let (_, tag) = nbt::from_bytes(&bytes, Compression::Gzip)?;
+
+		Ok(Some(LevelData::from_nbt(&tag)?))
+	}
+
+	/// Writes `level.dat`, keeping the previous copy as `level.dat_old` the
+	/// same way the Alpha server does.
+	pub fn save_level(&self, level: &LevelData) -> Result<()> {
+		let bytes = nbt::to_bytes("", &level.to_nbt(), Compression::Gzip)?;
+
+		let current = self.root.join(LEVEL_FILE);
+		let new = self.root.join("level.dat_new");
+		let old = self.root.join("level.dat_old");
+
+		fs::write(&new, bytes)?;
+		if current.exists() {
+			if old.exists() {
+				fs::remove_file(&old)?;
+			}
+			fs::rename(&current, &old)?;
+		}
+		fs::rename(&new, &current)?;
+
+		Ok(())
+	}
+
+	pub fn load_chunk(&self, x: i32, z: i32) -> Result<Option<Chunk>> {
+		let Some(bytes) = read_optional(&self.chunk_path(x, z))? else {
+			return Ok(None);
+		};
+		let (_, tag) = nbt::from_bytes(&bytes, Compression::Gzip)?;
+		let chunk = Chunk::from_nbt(&tag)?;
+		ensure!(
+			(chunk.x, chunk.z) == (x, z),
+			MisplacedChunkSnafu {
+				x,
+				z,
+				found_x: chunk.x,
+				found_z: chunk.z,
+			}
+		);
+
+		Ok(Some(chunk))
+	}
+
+	pub fn save_chunk(&self, chunk: &Chunk, time: i64) -> Result<()> {
+		let path = self.chunk_path(chunk.x, chunk.z);
+		let directory = path.parent().expect("chunk paths have a parent");
+		fs::create_dir_all(directory)?;
+
+		// Write to a temporary file first so a crash never leaves a
+		// truncated chunk behind.
+		let temporary = directory.join("tmp_chunk.dat");
+		let bytes = nbt::to_bytes("", &chunk.to_nbt(time), Compression::Gzip)?;
+		fs::write(&temporary, bytes)?;
+		fs::rename(&temporary, &path)?;
+
+		Ok(())
+	}
+
+	/// Restores a player from their file, leaving them as they are if they
+	/// have never played here before.
+	pub fn load_player(&self, player: &mut Player) -> Result<()> {
+		let path = self.player_path(&player.username)?;
+		let Some(bytes) = read_optional(&path)? else {
+			return Ok(());
+		};
+		let (_, tag) = nbt::from_bytes(&bytes, Compression::Gzip)?;
+
+		Ok(player.load_nbt(&tag)?)
+	}
+
+	pub fn save_player(&self, player: &Player) -> Result<()> {
+		let path = self.player_path(&player.username)?;
+		let directory = path.parent().expect("player paths have a parent");
+		fs::create_dir_all(directory)?;
+
+		// Written the same way as chunks, so a crash never leaves a
+		// truncated file behind.
+		let temporary = path.with_extension("dat.tmp");
+		let bytes = nbt::to_bytes("", &player.to_nbt(), Compression::Gzip)?;
+		fs::write(&temporary, bytes)?;
+		fs::rename(&temporary, &path)?;
+
+		Ok(())
+	}
+
+	/// Returns the total size of the world directory in bytes.
+	pub fn size_on_disk(&self) -> u64 {
+		fn directory_size(path: &Path) -> u64 {
+			let Ok(entries) = fs::read_dir(path) else {
+				return 0;
+			};
+
+			entries
+				.flatten()
+				.map(|entry| match entry.metadata() {
+					Ok(metadata) if metadata.is_dir() => {
+						directory_size(&entry.path())
+					}
+					Ok(metadata) => metadata.len(),
+					Err(_) => 0,
+				})
+				.sum()
+		}
+
+		directory_size(&self.root)
+	}
+
+	fn chunk_path(&self, x: i32, z: i32) -> PathBuf {
+		self.root
+			.join(base36(x & 63))
+			.join(base36(z & 63))
+			.join(format!("c.{}.{}.dat", base36(x), base36(z)))
+	}
+
+	/// Only names made of the characters the client allows in usernames
+	/// are turned into paths, so nobody can name a file outside the
+	/// `players` directory.
+	fn player_path(&self, username: &str) -> Result<PathBuf> {
+		let valid = !username.is_empty()
+			&& username
+				.chars()
+				.all(|c| c.is_ascii_alphanumeric() || c == '_');
+		ensure!(valid, InvalidPlayerNameSnafu { username });
+
+		Ok(self.root.join("players").join(format!("{username}.dat")))
+	}
+}
+
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>> {
+	match fs::read(path) {
+		Ok(bytes) => Ok(Some(bytes)),
+		Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+		Err(err) => Err(err.into()),
+	}
+}
+
+/// Formats a number the way Java's `Integer.toString(value, 36)` does.
+fn base36(value: i32) -> String {
+	const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+	let mut magnitude = value.unsigned_abs();
+	let mut digits = Vec::new();
+	loop {
+		digits.push(DIGITS[(magnitude % 36) as usize]);
+		magnitude /= 36;
+		if magnitude == 0 {
+			break;
+		}
+	}
+	if value < 0 {
+		digits.push(b'-');
+	}
+	digits.reverse();
+
+	String::from_utf8(digits).expect("base36 digits are ASCII")
+}
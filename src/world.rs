@@ -0,0 +1,278 @@
+use crate::{storage::Storage, Result};
+use oxidized_alpha::{
+	blocks,
+	chunk::CHUNK_HEIGHT,
+	generator::{GeneratorSettings, WorldGenerator},
+	level::LevelData,
+	light::Lighting,
+	random::JavaRandom,
+	Chunk, Player,
+};
+use std::{
+	collections::{hash_map::RandomState, HashMap, HashSet},
+	hash::{BuildHasher, Hasher},
+	path::Path,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The authoritative world state, owned by the server tick loop.
+pub struct World {
+	pub time: i64,
+	level: LevelData,
+	chunks: HashMap<(i32, i32), Chunk>,
+	/// Chunks that changed since they were last written to disk.
+	dirty: HashSet<(i32, i32)>,
+	storage: Storage,
+	generator: Box<dyn WorldGenerator>,
+}
+
+impl World {
+	/// Opens the world stored in `path`, creating a new one from `seed`, or
+	/// a random seed, if it does not exist yet. New chunks come from the
+	/// generator `settings` describe.
+	pub fn open(
+		path: impl AsRef<Path>,
+		settings: &GeneratorSettings,
+		seed: Option<i64>,
+	) -> Result<Self> {
+		let storage = Storage::open(path)?;
+		let existing = storage.load_level()?;
+		let created = existing.is_none();
+		let level = match existing {
+			Some(level) => level,
+			None => LevelData {
+				seed: seed.unwrap_or_else(|| {
+					RandomState::new().build_hasher().finish() as i64
+				}),
+				..Default::default()
+			},
+		};
+
+		tracing::info!("Loaded world with seed {}", level.seed);
+
+		let mut world = Self {
+			time: level.time,
+			generator: settings.build(level.seed),
+			level,
+			chunks: HashMap::new(),
+			dirty: HashSet::new(),
+			storage,
+		};
+		if created {
+			world.find_spawn();
+			world.save()?;
+		}
+
+		Ok(world)
+	}
+
+	pub fn seed(&self) -> i64 {
+		self.level.seed
+	}
+
+	pub fn spawn(&self) -> (i32, i32, i32) {
+		(self.level.spawn_x, self.level.spawn_y, self.level.spawn_z)
+	}
+
+	/// Advances the world by one tick.
+	pub fn tick(&mut self) {
+		self.time += 1;
+	}
+
+	/// Returns the chunk at the given chunk coordinates, loading it from disk
+	/// or generating it if it is not in memory yet.
+	pub fn chunk(&mut self, x: i32, z: i32) -> &Chunk {
+		if !self.chunks.contains_key(&(x, z)) {
+			let (chunk, generated) = match self.storage.load_chunk(x, z) {
+				Ok(Some(chunk)) => (chunk, false),
+				Ok(None) => {
+					self.dirty.insert((x, z));
+					(self.generator.generate(x, z), true)
+				}
+				Err(err) => {
+					tracing::error!("Failed to load chunk {x}, {z}: {err}");
+					(self.generator.generate(x, z), true)
+				}
+			};
+			self.chunks.insert((x, z), chunk);
+			// Saved chunks were lit across their edges when they were
+			// generated.
+			if generated {
+				self.relight_chunk(x, z);
+			}
+			self.populate_around(x, z);
+		}
+
+		&self.chunks[&(x, z)]
+	}
+
+	/// Returns the block and its metadata at the given world coordinates.
+	/// Everything above and below the world is air.
+	pub fn block(&mut self, x: i32, y: i32, z: i32) -> (u8, u8) {
+		if !(0..CHUNK_HEIGHT as i32).contains(&y) {
+			return (blocks::AIR, 0);
+		}
+		let chunk = self.chunk(x >> 4, z >> 4);
+		let (x, y, z) = ((x & 15) as usize, y as usize, (z & 15) as usize);
+
+		(chunk.get_block(x, y, z), chunk.get_meta(x, y, z))
+	}
+
+	/// Replaces the block at the given world coordinates, relights around it
+	/// and marks what changed for saving. Returns `false` if the coordinates
+	/// are outside the world.
+	pub fn set_block(
+		&mut self,
+		x: i32,
+		y: i32,
+		z: i32,
+		block: u8,
+		meta: u8,
+	) -> bool {
+		if !(0..CHUNK_HEIGHT as i32).contains(&y) {
+			return false;
+		}
+		let (chunk_x, chunk_z) = (x >> 4, z >> 4);
+		self.chunk(chunk_x, chunk_z);
+		let chunk = self
+			.chunks
+			.get_mut(&(chunk_x, chunk_z))
+			.expect("chunk was just loaded");
+		let local = ((x & 15) as usize, y as usize, (z & 15) as usize);
+		chunk.set_block(local.0, local.1, local.2, block);
+		chunk.set_meta(local.0, local.1, local.2, meta);
+		self.dirty.insert((chunk_x, chunk_z));
+		self.with_lighting(chunk_x, chunk_z, |lighting| {
+			lighting.update(x, y, z)
+		});
+
+		true
+	}
+
+	/// Lights a chunk from scratch, along with the edges of the loaded
+	/// chunks around it.
+	fn relight_chunk(&mut self, x: i32, z: i32) {
+		self.with_lighting(x, z, |lighting| lighting.light_chunk(x, z));
+	}
+
+	/// Runs a lighting pass over the chunk at `(x, z)` and whichever of its
+	/// neighbours are loaded, marking every chunk it changed for saving.
+	fn with_lighting(
+		&mut self,
+		x: i32,
+		z: i32,
+		pass: impl FnOnce(&mut Lighting),
+	) {
+		let mut around = [(0, 0); 9];
+		for (i, position) in around.iter_mut().enumerate() {
+			*position = (x + i as i32 % 3 - 1, z + i as i32 / 3 - 1);
+		}
+		let mut chunks: Vec<_> = self
+			.chunks
+			.get_disjoint_mut(around.each_ref())
+			.into_iter()
+			.flatten()
+			.collect();
+
+		let mut lighting = Lighting::new(&mut chunks);
+		pass(&mut lighting);
+		self.dirty.extend(lighting.changed_chunks());
+	}
+
+	/// Populates every chunk whose group of four the chunk at `(x, z)` just
+	/// completed, as the Alpha server does when chunks are loaded.
+	fn populate_around(&mut self, x: i32, z: i32) {
+		for (x, z) in [(x, z), (x - 1, z), (x, z - 1), (x - 1, z - 1)] {
+			let group = [(x, z), (x + 1, z), (x, z + 1), (x + 1, z + 1)];
+			let chunks = self.chunks.get_disjoint_mut(group.each_ref());
+			let Some(mut chunks) =
+				chunks.into_iter().collect::<Option<Vec<_>>>()
+			else {
+				continue;
+			};
+			if chunks[0].populated {
+				continue;
+			}
+
+			self.generator.populate(&mut chunks, x, z);
+			self.dirty.extend(group);
+			for (x, z) in group {
+				self.relight_chunk(x, z);
+			}
+		}
+	}
+
+	/// Picks the spawn point of a new world the way Alpha does: wander
+	/// randomly from the origin until the generator accepts the ground.
+	fn find_spawn(&mut self) {
+		const ATTEMPTS: usize = 1000;
+
+		let mut random = JavaRandom::new(self.seed());
+		let (mut x, mut z) = (0, 0);
+		for _ in 0..ATTEMPTS {
+			let (block, _) = self.top_block(x, z);
+			if self.generator.is_spawn_block(block) {
+				break;
+			}
+			x += random.next_int(64) - random.next_int(64);
+			z += random.next_int(64) - random.next_int(64);
+		}
+
+		self.level.spawn_x = x;
+		self.level.spawn_y = self.top_block(x, z).1 + 1;
+		self.level.spawn_z = z;
+	}
+
+	/// Returns the highest non-air block in a column and its height.
+	fn top_block(&mut self, x: i32, z: i32) -> (u8, i32) {
+		let chunk = self.chunk(x >> 4, z >> 4);
+		let (local_x, local_z) = ((x & 15) as usize, (z & 15) as usize);
+
+		(0..CHUNK_HEIGHT)
+			.rev()
+			.map(|y| (chunk.get_block(local_x, y, local_z), y as i32))
+			.find(|(block, _)| *block != blocks::AIR)
+			.unwrap_or((blocks::AIR, 0))
+	}
+
+	/// Restores a player from the world's player files, if they have played
+	/// here before.
+	pub fn load_player(&self, player: &mut Player) -> Result<()> {
+		self.storage.load_player(player)
+	}
+
+	pub fn save_player(&self, player: &Player) -> Result<()> {
+		self.storage.save_player(player)
+	}
+
+	/// Drops the chunks that are not `in_use` from memory, unless they have
+	/// changes that are not on disk yet. They are loaded again the next
+	/// time something needs them. Returns how many were dropped.
+	pub fn unload_chunks(&mut self, in_use: &HashSet<(i32, i32)>) -> usize {
+		let before = self.chunks.len();
+		self.chunks.retain(|position, _| {
+			in_use.contains(position) || self.dirty.contains(position)
+		});
+
+		before - self.chunks.len()
+	}
+
+	/// Writes every dirty chunk and `level.dat` to disk. Chunks stay dirty
+	/// until they are written, so a failed save is retried by the next one.
+	pub fn save(&mut self) -> Result<()> {
+		let dirty: Vec<_> = self.dirty.iter().copied().collect();
+		for position in dirty {
+			if let Some(chunk) = self.chunks.get(&position) {
+				self.storage.save_chunk(chunk, self.time)?;
+			}
+			self.dirty.remove(&position);
+		}
+
+		self.level.time = self.time;
+		self.level.last_played = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |duration| duration.as_millis() as i64);
+		self.level.size_on_disk = self.storage.size_on_disk() as i64;
+		self.storage.save_level(&self.level)
+	}
+}
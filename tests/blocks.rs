@@ -0,0 +1,79 @@
+use oxidized_alpha::{
+	blocks,
+	items::{self, Tool, ToolKind},
+	random::JavaRandom,
+	ItemStack,
+};
+
+fn pickaxe(tier: u8) -> Option<Tool> {
+	Some(Tool {
+		kind: ToolKind::Pickaxe,
+		tier,
+	})
+}
+
+#[test]
+fn looks_up_blocks_and_items_by_id() {
+	let stone = blocks::get(blocks::STONE).unwrap();
+	assert_eq!(stone.name, "Stone");
+	assert_eq!(stone.light_opacity, 15);
+	assert_eq!(blocks::get(blocks::TORCH).unwrap().light_emission, 14);
+	assert!(blocks::get(21).is_none());
+
+	let pickaxe = items::get(items::DIAMOND_PICKAXE).unwrap();
+	assert_eq!(pickaxe.max_stack_size, 1);
+	assert_eq!(pickaxe.max_damage, 256);
+	assert_eq!(pickaxe.tool, self::pickaxe(3));
+
+	assert!(items::exists(blocks::GLOWSTONE as i16));
+	assert!(items::exists(items::GREEN_RECORD));
+	assert!(!items::exists(blocks::AIR as i16));
+	assert!(!items::exists(351));
+	assert_eq!(items::max_stack_size(items::SIGN), 1);
+	assert_eq!(items::max_stack_size(items::SNOWBALL), 16);
+}
+
+#[test]
+fn drops_only_when_broken_with_the_right_tool() {
+	let mut random = JavaRandom::new(0);
+	let stone = blocks::get(blocks::STONE).unwrap();
+	let iron_ore = blocks::get(blocks::IRON_ORE).unwrap();
+
+	assert_eq!(stone.drops_for(None, &mut random), None);
+	assert_eq!(
+		stone.drops_for(pickaxe(0), &mut random),
+		Some(ItemStack {
+			id: blocks::COBBLESTONE as i16,
+			count: 1,
+			damage: 0,
+		})
+	);
+	assert_eq!(iron_ore.drops_for(pickaxe(0), &mut random), None);
+	assert!(iron_ore.drops_for(pickaxe(1), &mut random).is_some());
+	assert!(blocks::get(blocks::DIRT)
+		.unwrap()
+		.drops_for(None, &mut random)
+		.is_some());
+}
+
+#[test]
+fn drops_a_random_amount_within_the_range() {
+	let mut random = JavaRandom::new(0);
+	let ore = blocks::get(blocks::REDSTONE_ORE).unwrap();
+
+	for _ in 0..100 {
+		let drop = ore.drops_for(pickaxe(2), &mut random).unwrap();
+		assert_eq!(drop.id, items::REDSTONE);
+		assert!((4..=5).contains(&drop.count));
+	}
+}
+
+#[test]
+fn registers_every_alpha_block() {
+	let ids: Vec<u8> = (0..=u8::MAX)
+		.filter_map(blocks::get)
+		.map(|block| block.id)
+		.collect();
+	assert_eq!(ids.len(), 77);
+	assert_eq!(ids.last(), Some(&blocks::JACK_O_LANTERN));
+}
@@ -0,0 +1,90 @@
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+use oxidized_alpha::{
+	chunk::{block_index, NibbleArray, CHUNK_VOLUME},
+	nbt::{Compound, Tag},
+	packets::ClientboundPacket,
+	Chunk, Error,
+};
+
+/// The root compound of a chunk file whose `Data` array is `data_length`
+/// bytes long.
+fn chunk_nbt(data_length: usize) -> Tag {
+	let nibbles = Tag::ByteArray(vec![0; CHUNK_VOLUME / 2]);
+	let level = Compound::new()
+		.with("xPos", Tag::Int(0))
+		.with("zPos", Tag::Int(0))
+		.with("Blocks", Tag::ByteArray(vec![0; CHUNK_VOLUME]))
+		.with("Data", Tag::ByteArray(vec![0; data_length]))
+		.with("SkyLight", nibbles.clone())
+		.with("BlockLight", nibbles);
+
+	Tag::Compound(Compound::new().with("Level", Tag::Compound(level)))
+}
+
+#[test]
+fn packs_even_indices_into_the_low_nibble() {
+	let mut nibbles = NibbleArray::default();
+	nibbles.set(0, 0xA);
+	nibbles.set(1, 0x5);
+	nibbles.set(3, 0x1F);
+
+	assert_eq!(&nibbles.as_bytes()[..2], [0x5A, 0xF0]);
+	assert_eq!(nibbles.get(0), 0xA);
+	assert_eq!(nibbles.get(1), 0x5);
+	assert_eq!(nibbles.get(2), 0);
+	assert_eq!(nibbles.get(3), 0xF);
+	assert_eq!(NibbleArray::filled(7).as_bytes()[100], 0x77);
+}
+
+#[test]
+fn indexes_blocks_by_y_then_z_then_x() {
+	assert_eq!(block_index(0, 1, 0), 1);
+	assert_eq!(block_index(0, 0, 1), 128);
+	assert_eq!(block_index(1, 0, 0), 2048);
+	assert_eq!(block_index(15, 127, 15), CHUNK_VOLUME - 1);
+
+	let mut chunk = Chunk::new(0, 0);
+	chunk.set_block(3, 70, 5, 1);
+	chunk.set_meta(3, 70, 5, 9);
+	let index = 70 + 5 * 128 + 3 * 2048;
+	assert_eq!(chunk.blocks[index], 1);
+	assert_eq!(chunk.data.get(index), 9);
+}
+
+#[test]
+fn sends_the_whole_column_in_one_packet() {
+	let mut chunk = Chunk::new(2, -3);
+	chunk.set_block(1, 2, 3, 4);
+	let ClientboundPacket::MapChunk {
+		x,
+		y,
+		z,
+		size_x,
+		size_y,
+		size_z,
+		compressed_data,
+	} = chunk.to_packet()
+	else {
+		panic!("expected a MAP_CHUNK packet");
+	};
+
+	assert_eq!((x, y, z), (32, 0, -48));
+	assert_eq!((size_x, size_y, size_z), (15, 127, 15));
+	let data = decompress_to_vec_zlib(&compressed_data).unwrap();
+	// Block ids, then metadata, block light and sky light at half a byte each.
+	assert_eq!(data.len(), CHUNK_VOLUME * 5 / 2);
+	assert_eq!(data[block_index(1, 2, 3)], 4);
+}
+
+#[test]
+fn rejects_arrays_of_the_wrong_length() {
+	assert!(Chunk::from_nbt(&chunk_nbt(CHUNK_VOLUME / 2)).is_ok());
+	assert!(matches!(
+		Chunk::from_nbt(&chunk_nbt(100)),
+		Err(Error::InvalidLength { length: 100 })
+	));
+	assert!(matches!(
+		NibbleArray::from_bytes(&[0; CHUNK_VOLUME]),
+		Err(Error::InvalidLength { length }) if length == CHUNK_VOLUME as i64
+	));
+}
@@ -0,0 +1,84 @@
+use oxidized_alpha::{
+	entity::{ItemEntity, ITEM_LIFETIME},
+	packets::ClientboundPacket,
+	Health, Inventory, ItemStack, Player,
+};
+
+fn item_at(x: f64, y: f64, z: f64) -> ItemEntity {
+	ItemEntity {
+		id: 7,
+		item: ItemStack {
+			id: 3,
+			count: 1,
+			damage: 0,
+		},
+		x,
+		y,
+		z,
+		velocity_x: 0.0,
+		velocity_y: 0.2,
+		velocity_z: 0.0,
+		age: 0,
+		pickup_delay: 10,
+	}
+}
+
+#[test]
+fn falls_until_it_lands_on_solid_ground() {
+	let mut item = item_at(0.5, 64.5, 0.5);
+	for _ in 0..100 {
+		item.tick(|_, y, _| y < 60);
+	}
+
+	assert_eq!(item.y, 60.0);
+	assert_eq!(item.velocity_y, 0.0);
+	assert!(item.can_be_picked_up());
+	assert!(!item.is_expired());
+
+	item.age = ITEM_LIFETIME;
+	assert!(item.is_expired());
+}
+
+#[test]
+fn is_picked_up_by_players_within_reach() {
+	let player = Player {
+		username: "steve".into(),
+		logged_in: true,
+		x: 0.5,
+		y: 64.0,
+		z: 0.5,
+		yaw: 0.0,
+		pitch: 0.0,
+		stance: 65.62,
+		on_ground: true,
+		inventory: Inventory::default(),
+		held_item: 0,
+		health: Health::default(),
+	};
+
+	assert!(item_at(1.5, 64.0, 0.5).is_near(&player));
+	assert!(item_at(0.5, 65.5, -0.5).is_near(&player));
+	assert!(!item_at(2.5, 64.0, 0.5).is_near(&player));
+	assert!(!item_at(0.5, 62.0, 0.5).is_near(&player));
+}
+
+#[test]
+fn spawns_with_its_velocity_in_the_rotation_fields() {
+	let mut item = item_at(-1.5, 64.0, 2.0);
+	item.velocity_x = -0.1;
+
+	assert_eq!(
+		item.to_packet(),
+		ClientboundPacket::PickupSpawn {
+			entity_id: 7,
+			item_id: 3,
+			count: 1,
+			x: -48,
+			y: 2048,
+			z: 64,
+			rotation: -12,
+			pitch: 25,
+			roll: 0,
+		}
+	);
+}
@@ -0,0 +1,256 @@
+use oxidized_alpha::{
+	blocks,
+	chunk::{CHUNK_HEIGHT, CHUNK_WIDTH},
+	generator::{
+		alpha::SEA_LEVEL, void::PLATFORM_Y, AlphaGenerator,
+		CheckerboardGenerator, FlatGenerator, GeneratorSettings, VoidGenerator,
+		WorldGenerator,
+	},
+	random::JavaRandom,
+	Chunk, Error,
+};
+
+/// FNV-1a over the blocks and metadata, to pin a chunk in a single number.
+fn fingerprint(chunk: &Chunk) -> u64 {
+	chunk
+		.blocks
+		.iter()
+		.chain(chunk.data.as_bytes())
+		.fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+			(hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+		})
+}
+
+/// Generates the four chunks populating `(x, z)` touches and populates it.
+fn populated(generator: &AlphaGenerator, x: i32, z: i32) -> Vec<Chunk> {
+	let mut chunks = vec![
+		generator.generate(x, z),
+		generator.generate(x + 1, z),
+		generator.generate(x, z + 1),
+		generator.generate(x + 1, z + 1),
+	];
+	let mut group = chunks.iter_mut().collect::<Vec<_>>();
+	generator.populate(&mut group, x, z);
+
+	chunks
+}
+
+#[test]
+fn matches_java_random() {
+	let mut random = JavaRandom::new(0);
+	assert_eq!(random.next_long(), -4962768465676381896);
+
+	let mut random = JavaRandom::new(0);
+	assert_eq!(random.next_double(), 0.730967787376657);
+
+	let mut random = JavaRandom::new(42);
+	let rolls = (0..8).map(|_| random.next_int(10)).collect::<Vec<_>>();
+	assert_eq!(rolls, [0, 3, 8, 4, 0, 5, 5, 8]);
+}
+
+#[test]
+fn generates_the_same_chunk_for_the_same_seed() {
+	let first = AlphaGenerator::new(42).generate(3, -7);
+	let second = AlphaGenerator::new(42).generate(3, -7);
+
+	assert_eq!(first, second);
+	assert_ne!(
+		fingerprint(&first),
+		fingerprint(&AlphaGenerator::new(43).generate(3, -7))
+	);
+}
+
+#[test]
+fn lays_bedrock_and_keeps_water_below_sea_level() {
+	let generator = AlphaGenerator::new(12345);
+
+	for (x, z) in [(0, 0), (-5, 2), (17, -30)] {
+		let chunk = generator.generate(x, z);
+		for bx in 0..CHUNK_WIDTH {
+			for bz in 0..CHUNK_WIDTH {
+				assert_eq!(chunk.get_block(bx, 0, bz), blocks::BEDROCK);
+				for y in SEA_LEVEL as usize..CHUNK_HEIGHT {
+					assert_ne!(chunk.get_block(bx, y, bz), blocks::WATER);
+				}
+			}
+		}
+		assert!(!chunk.populated);
+	}
+}
+
+#[test]
+fn pins_generated_terrain() {
+	for (seed, x, z, expected) in [
+		(0, 0, 0, 10980390012643249135),
+		(12345, 0, 0, 12603075991031570145),
+		(12345, -9, 4, 4117632616316494760),
+		(-3_141_592_653, 25, -13, 1784534839933535347),
+	] {
+		let chunk = AlphaGenerator::new(seed).generate(x, z);
+		assert_eq!(
+			fingerprint(&chunk),
+			expected,
+			"seed {seed}, chunk {x}, {z}"
+		);
+	}
+}
+
+#[test]
+fn pins_populated_terrain() {
+	for (seed, x, z, expected) in [
+		(0, 0, 0, 10366158993665051166),
+		(12345, -9, 4, 4639887978523915994),
+	] {
+		let chunks = populated(&AlphaGenerator::new(seed), x, z);
+		assert!(chunks[0].populated);
+		assert!(chunks[1..].iter().all(|chunk| !chunk.populated));
+		assert_eq!(
+			fingerprint(&chunks[0]),
+			expected,
+			"seed {seed}, chunk {x}, {z}"
+		);
+	}
+}
+
+#[test]
+fn populating_places_ores_and_trees() {
+	let generator = AlphaGenerator::new(12345);
+	let count = |chunks: &[Chunk], block| {
+		chunks
+			.iter()
+			.flat_map(|chunk| chunk.blocks.iter())
+			.filter(|id| **id == block)
+			.count()
+	};
+
+	let raw = [
+		generator.generate(0, 0),
+		generator.generate(1, 0),
+		generator.generate(0, 1),
+		generator.generate(1, 1),
+	];
+	let chunks = populated(&generator, 0, 0);
+
+	assert_eq!(count(&raw, blocks::COAL_ORE), 0);
+	assert!(count(&chunks, blocks::COAL_ORE) > 0);
+	assert!(count(&chunks, blocks::IRON_ORE) > 0);
+	assert!(count(&chunks, blocks::LOG) > 0);
+	assert!(count(&chunks, blocks::LEAVES) > 0);
+}
+
+#[test]
+fn stacks_flat_layers_from_the_bottom() {
+	let generator = FlatGenerator::from_preset("7, 3x3 ,2").unwrap();
+	assert_eq!(
+		generator.layers(),
+		[
+			blocks::BEDROCK,
+			blocks::DIRT,
+			blocks::DIRT,
+			blocks::DIRT,
+			blocks::GRASS
+		]
+	);
+
+	let chunk = generator.generate(-4, 9);
+	for x in 0..CHUNK_WIDTH {
+		for z in 0..CHUNK_WIDTH {
+			assert_eq!(chunk.get_block(x, 0, z), blocks::BEDROCK);
+			assert_eq!(chunk.get_block(x, 3, z), blocks::DIRT);
+			assert_eq!(chunk.get_block(x, 4, z), blocks::GRASS);
+			assert_eq!(chunk.get_block(x, 5, z), blocks::AIR);
+			assert_eq!(chunk.get_sky_light(x, 5, z), 15);
+			assert_eq!(chunk.get_sky_light(x, 4, z), 0);
+		}
+	}
+}
+
+#[test]
+fn rejects_invalid_flat_presets() {
+	for preset in [
+		"",
+		"7,,2",
+		"0x3",
+		"2xstone",
+		"256",
+		"129x1",
+		"7,21",
+		"2x95",
+		"99999999999999x1",
+		"4000000000x1",
+		"100x1,100x1",
+	] {
+		assert!(
+			matches!(
+				FlatGenerator::from_preset(preset),
+				Err(Error::InvalidGeneratorSettings { .. })
+			),
+			"{preset:?}"
+		);
+	}
+}
+
+#[test]
+fn leaves_only_a_platform_in_the_void() {
+	let generator = VoidGenerator;
+	let solid = |chunk: &Chunk| {
+		chunk.blocks.iter().filter(|id| **id != blocks::AIR).count()
+	};
+
+	let origin = generator.generate(0, 0);
+	assert_eq!(origin.get_block(0, PLATFORM_Y, 0), blocks::STONE);
+	assert_eq!(solid(&origin), 9);
+	assert_eq!(solid(&generator.generate(-1, -1)), 4);
+	assert_eq!(solid(&generator.generate(5, 5)), 0);
+}
+
+#[test]
+fn alternates_checkerboard_blocks_by_chunk() {
+	let generator = CheckerboardGenerator::new([blocks::STONE, blocks::SAND]);
+
+	assert_eq!(generator.generate(0, 0).get_block(3, 10, 3), blocks::STONE);
+	assert_eq!(generator.generate(1, 0).get_block(3, 10, 3), blocks::SAND);
+	assert_eq!(generator.generate(-1, 0).get_block(3, 10, 3), blocks::SAND);
+	assert_eq!(generator.generate(-1, 1).get_block(3, 10, 3), blocks::STONE);
+	assert_eq!(generator.generate(0, 0).get_block(3, 0, 3), blocks::BEDROCK);
+}
+
+#[test]
+fn parses_generator_settings() {
+	assert_eq!(
+		GeneratorSettings::parse("DEFAULT", "").unwrap(),
+		GeneratorSettings::Alpha
+	);
+	assert_eq!(
+		GeneratorSettings::parse("flat", "").unwrap(),
+		GeneratorSettings::Flat(FlatGenerator::default())
+	);
+	assert_eq!(
+		GeneratorSettings::parse("flat", "7,2").unwrap(),
+		GeneratorSettings::Flat(FlatGenerator::from_preset("7,2").unwrap())
+	);
+	assert_eq!(
+		GeneratorSettings::parse("void", "").unwrap(),
+		GeneratorSettings::Void
+	);
+	assert_eq!(
+		GeneratorSettings::parse("checkerboard", "1,12").unwrap(),
+		GeneratorSettings::Checkerboard(CheckerboardGenerator::new([
+			blocks::STONE,
+			blocks::SAND
+		]))
+	);
+
+	assert!(matches!(
+		GeneratorSettings::parse("amplified", ""),
+		Err(Error::UnknownLevelType { .. })
+	));
+	assert!(matches!(
+		GeneratorSettings::parse("checkerboard", "1,2,3"),
+		Err(Error::InvalidGeneratorSettings { .. })
+	));
+	assert!(matches!(
+		GeneratorSettings::parse("checkerboard", "1,200"),
+		Err(Error::InvalidGeneratorSettings { .. })
+	));
+}
@@ -0,0 +1,90 @@
+use oxidized_alpha::{
+	health::{Surroundings, MAX_AIR, MAX_HEALTH},
+	Health,
+};
+
+/// Lets the player recover from being hurt.
+fn wait_out_invulnerability(health: &mut Health) {
+	for _ in 0..10 {
+		health.tick(Surroundings::default());
+	}
+}
+
+#[test]
+fn hurts_players_who_fall_too_far() {
+	let mut health = Health::default();
+	for _ in 0..6 {
+		assert!(!health.fall(-0.5, false));
+	}
+	assert!(!health.fall(0.0, true));
+	assert_eq!(health.health, MAX_HEALTH);
+
+	for _ in 0..9 {
+		health.fall(-0.5, false);
+	}
+	assert!(health.fall(0.0, true));
+	assert_eq!(health.health, MAX_HEALTH - 2);
+	assert_eq!(health.fall_distance, 0.0);
+}
+
+#[test]
+fn kills_players_who_report_huge_falls() {
+	let mut health = Health::default();
+	health.fall(-1e12, false);
+
+	assert!(health.fall(0.0, true));
+	assert_eq!(health.health, 0);
+	assert!(health.is_dead());
+}
+
+#[test]
+fn ignores_damage_while_recovering_from_a_hit() {
+	let mut health = Health::default();
+	let lava = Surroundings {
+		in_lava: true,
+		..Default::default()
+	};
+
+	assert!(health.tick(lava));
+	for _ in 0..9 {
+		assert!(!health.tick(lava));
+	}
+	assert!(health.tick(lava));
+	assert_eq!(health.health, MAX_HEALTH - 8);
+}
+
+#[test]
+fn drowns_players_who_run_out_of_air() {
+	let mut health = Health::default();
+	let under_water = Surroundings {
+		in_water: true,
+		head_in_water: true,
+		..Default::default()
+	};
+
+	for _ in 0..MAX_AIR + 19 {
+		assert!(!health.tick(under_water));
+	}
+	assert!(health.tick(under_water));
+	assert_eq!(health.health, MAX_HEALTH - 2);
+
+	health.tick(Surroundings::default());
+	assert_eq!(health.air, MAX_AIR);
+}
+
+#[test]
+fn stays_dead_once_out_of_health() {
+	let mut health = Health::default();
+	let void = Surroundings {
+		in_void: true,
+		..Default::default()
+	};
+
+	while !health.is_dead() {
+		health.tick(void);
+		wait_out_invulnerability(&mut health);
+	}
+	assert_eq!(health.health, 0);
+	assert!(!health.damage(1));
+	assert!(!health.tick(void));
+}
@@ -0,0 +1,133 @@
+use oxidized_alpha::{
+	bytes::ByteWriter,
+	inventory::{Section, HOTBAR_SIZE},
+	Inventory, ItemStack,
+};
+
+fn stack(id: i16, count: u8) -> Option<ItemStack> {
+	Some(ItemStack {
+		id,
+		count,
+		damage: 0,
+	})
+}
+
+#[test]
+fn encodes_a_section_as_a_player_inventory_packet() {
+	let mut inventory = Inventory::default();
+	inventory
+		.replace(-2, &[None, stack(307, 1), None, None])
+		.unwrap();
+
+	let mut writer = ByteWriter::new();
+	inventory
+		.to_packet(Section::Armor)
+		.encode(&mut writer)
+		.unwrap();
+
+	assert_eq!(
+		writer.as_bytes(),
+		[
+			0x05, 0xff, 0xff, 0xff, 0xfe, 0x00, 0x04, 0xff, 0xff, 0x01, 0x33,
+			0x01, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+		]
+	);
+}
+
+#[test]
+fn rejects_sections_that_could_not_exist() {
+	let mut inventory = Inventory::default();
+	let mut main = vec![None; Section::Main.size()];
+	main[0] = stack(4, 64);
+	inventory.replace(-1, &main).unwrap();
+	let before = inventory.clone();
+
+	assert!(inventory.replace(-4, &[None; 4]).is_err());
+	assert!(inventory.replace(-1, &main[..HOTBAR_SIZE]).is_err());
+	main[0] = stack(4, 65);
+	assert!(inventory.replace(-1, &main).is_err());
+	main[0] = stack(0, 1);
+	assert!(inventory.replace(-1, &main).is_err());
+	assert_eq!(inventory, before);
+}
+
+#[test]
+fn takes_items_from_the_hotbar_only() {
+	let mut inventory = Inventory::default();
+	let mut main = vec![None; Section::Main.size()];
+	main[3] = stack(1, 1);
+	main[HOTBAR_SIZE] = stack(3, 10);
+	inventory.replace(-1, &main).unwrap();
+
+	assert!(inventory.in_hotbar(1));
+	assert!(!inventory.in_hotbar(3));
+	assert!(!inventory.take_from_hotbar(3));
+	assert!(inventory.take_from_hotbar(1));
+	assert!(!inventory.in_hotbar(1));
+	assert_eq!(inventory.hotbar()[3], None);
+}
+
+#[test]
+fn adds_and_removes_all_of_the_items_or_none() {
+	let mut inventory = Inventory::default();
+	let mut main = vec![stack(1, 1); Section::Main.size()];
+	main[5] = stack(4, 60);
+	main[20] = None;
+	inventory.replace(-1, &main).unwrap();
+
+	assert!(!inventory.add(ItemStack {
+		id: 4,
+		count: 69,
+		damage: 0,
+	}));
+	assert!(inventory.add(ItemStack {
+		id: 4,
+		count: 10,
+		damage: 0,
+	}));
+	assert_eq!(inventory.section(Section::Main)[5], stack(4, 64));
+	assert_eq!(inventory.section(Section::Main)[20], stack(4, 6));
+
+	assert_eq!(inventory.remove(4, 71), None);
+	assert_eq!(inventory.remove(4, 8), stack(4, 8));
+	assert_eq!(inventory.section(Section::Main)[5], stack(4, 62));
+	assert_eq!(inventory.section(Section::Main)[20], None);
+}
+
+#[test]
+fn removes_items_with_the_damage_they_have() {
+	let mut inventory = Inventory::default();
+	let mut main = vec![None; Section::Main.size()];
+	let pickaxe = |damage| {
+		Some(ItemStack {
+			id: 257,
+			count: 1,
+			damage,
+		})
+	};
+	main[0] = pickaxe(12);
+	main[1] = pickaxe(40);
+	inventory.replace(-1, &main).unwrap();
+
+	assert_eq!(inventory.remove(257, 2), None);
+	assert_eq!(inventory.remove(257, 1), pickaxe(40));
+	assert_eq!(inventory.remove(257, 1), pickaxe(12));
+	assert!(inventory.section(Section::Main).iter().all(Option::is_none));
+}
+
+#[test]
+fn takes_everything_out_of_every_section() {
+	let mut inventory = Inventory::default();
+	let mut main = vec![None; Section::Main.size()];
+	main[20] = stack(4, 12);
+	inventory.replace(-1, &main).unwrap();
+	inventory
+		.replace(-2, &[stack(306, 1), None, None, None])
+		.unwrap();
+
+	assert_eq!(
+		inventory.take_all(),
+		[stack(4, 12).unwrap(), stack(306, 1).unwrap()]
+	);
+	assert_eq!(inventory, Inventory::default());
+}
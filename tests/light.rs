@@ -0,0 +1,111 @@
+use oxidized_alpha::{
+	blocks,
+	chunk::CHUNK_WIDTH,
+	light::{self, LightKind, Lighting},
+	Chunk,
+};
+
+/// A chunk with a stone roof at y = 64, dark underneath.
+fn roofed_chunk(x: i32, z: i32) -> Chunk {
+	let mut chunk = Chunk::new(x, z);
+	for x in 0..CHUNK_WIDTH {
+		for z in 0..CHUNK_WIDTH {
+			chunk.set_block(x, 64, z, blocks::STONE);
+		}
+	}
+	chunk
+}
+
+#[test]
+fn lights_the_sky_down_to_the_first_opaque_block() {
+	let mut chunk = roofed_chunk(0, 0);
+	chunk.set_block(3, 70, 3, blocks::LEAVES);
+	light::light_chunk(&mut chunk);
+
+	assert_eq!(chunk.get_sky_light(3, 71, 3), 15);
+	assert_eq!(chunk.get_sky_light(3, 69, 3), 14);
+	assert_eq!(chunk.get_sky_light(8, 65, 8), 15);
+	assert_eq!(chunk.get_sky_light(8, 64, 8), 0);
+	assert_eq!(chunk.get_sky_light(8, 20, 8), 0);
+}
+
+#[test]
+fn spreads_block_light_from_emitters() {
+	let mut chunk = roofed_chunk(0, 0);
+	chunk.set_block(8, 20, 8, blocks::TORCH);
+	light::light_chunk(&mut chunk);
+
+	assert_eq!(chunk.get_block_light(8, 20, 8), 14);
+	assert_eq!(chunk.get_block_light(9, 20, 8), 13);
+	assert_eq!(chunk.get_block_light(8, 10, 4), 0);
+	assert_eq!(chunk.get_block_light(8, 10, 5), 1);
+}
+
+#[test]
+fn updates_light_when_blocks_change() {
+	let mut chunk = roofed_chunk(0, 0);
+	chunk.set_block(8, 20, 8, blocks::TORCH);
+	light::light_chunk(&mut chunk);
+
+	chunk.set_block(8, 64, 8, blocks::AIR);
+	Lighting::new(&mut [&mut chunk]).update(8, 64, 8);
+	assert_eq!(chunk.get_sky_light(8, 30, 8), 15);
+	assert_eq!(chunk.get_sky_light(9, 30, 8), 14);
+
+	chunk.set_block(8, 64, 8, blocks::STONE);
+	chunk.set_block(8, 20, 8, blocks::AIR);
+	let mut chunks = [&mut chunk];
+	let mut lighting = Lighting::new(&mut chunks);
+	lighting.update(8, 64, 8);
+	lighting.update(8, 20, 8);
+	for y in 0..64 {
+		assert_eq!(lighting.light(LightKind::Sky, 9, y, 8), 0);
+		assert_eq!(lighting.light(LightKind::Block, 8, y, 8), 0);
+	}
+}
+
+#[test]
+fn spreads_light_across_chunk_edges() {
+	let (mut left, mut right) = (roofed_chunk(0, 0), roofed_chunk(1, 0));
+	left.set_block(15, 20, 8, blocks::GLOWSTONE);
+	light::light_chunk(&mut left);
+	light::light_chunk(&mut right);
+
+	let mut chunks = [&mut left, &mut right];
+	let mut lighting = Lighting::new(&mut chunks);
+	lighting.light_chunk(1, 0);
+	assert_eq!(lighting.light(LightKind::Block, 16, 20, 8), 14);
+	assert_eq!(lighting.light(LightKind::Block, 20, 20, 8), 10);
+	assert_eq!(lighting.changed_chunks().collect::<Vec<_>>(), [(1, 0)]);
+
+	chunks[0].set_block(15, 20, 8, blocks::AIR);
+	let mut lighting = Lighting::new(&mut chunks);
+	lighting.update(15, 20, 8);
+	assert_eq!(lighting.light(LightKind::Block, 20, 20, 8), 0);
+	assert_eq!(lighting.changed_chunks().count(), 2);
+}
+
+#[test]
+fn carries_sky_light_down_through_leaves_without_loss() {
+	let mut chunk = roofed_chunk(0, 0);
+	for x in 0..CHUNK_WIDTH {
+		for z in 0..CHUNK_WIDTH {
+			chunk.set_block(x, 80, z, blocks::STONE);
+		}
+	}
+	chunk.set_block(8, 80, 8, blocks::LEAVES);
+	light::light_chunk(&mut chunk);
+
+	assert_eq!(chunk.get_sky_light(8, 80, 8), 14);
+	assert_eq!(chunk.get_sky_light(8, 65, 8), 14);
+	assert_eq!(chunk.get_sky_light(9, 65, 8), 13);
+
+	chunk.set_block(4, 80, 4, blocks::GLASS);
+	Lighting::new(&mut [&mut chunk]).update(4, 80, 4);
+	assert_eq!(chunk.get_sky_light(4, 65, 4), 15);
+
+	chunk.set_block(4, 80, 4, blocks::STONE);
+	Lighting::new(&mut [&mut chunk]).update(4, 80, 4);
+	assert_eq!(chunk.get_sky_light(4, 65, 4), 6);
+	assert_eq!(chunk.get_sky_light(8, 65, 8), 14);
+}
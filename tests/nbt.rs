@@ -0,0 +1,239 @@
+use oxidized_alpha::{
+	chunk::Chunk,
+	level::LevelData,
+	nbt::{self, Compound, Compression, Tag},
+	Error,
+};
+
+const HELLO_WORLD: &[u8] = include_bytes!("fixtures/hello_world.nbt");
+const ALL_TAGS_GZIP: &[u8] = include_bytes!("fixtures/all_tags.nbt");
+const ALL_TAGS_ZLIB: &[u8] = include_bytes!("fixtures/all_tags.zlib.nbt");
+
+fn field<'a>(compound: &'a Compound, name: &str) -> &'a Tag {
+	compound
+		.get(name)
+		.unwrap_or_else(|| panic!("missing {name}"))
+}
+
+#[test]
+fn reads_hello_world() {
+	let (name, tag) = nbt::from_bytes(HELLO_WORLD, Compression::None).unwrap();
+
+	assert_eq!(name, "hello world");
+	assert_eq!(
+		tag,
+		Tag::Compound(
+			Compound::new().with("name", Tag::String("Bananrama".into()))
+		)
+	);
+}
+
+#[test]
+fn writes_hello_world_byte_for_byte() {
+	let (name, tag) = nbt::from_bytes(HELLO_WORLD, Compression::None).unwrap();
+
+	assert_eq!(
+		nbt::to_bytes(&name, &tag, Compression::None).unwrap(),
+		HELLO_WORLD
+	);
+}
+
+#[test]
+fn reads_every_tag_type() {
+	let (name, tag) =
+		nbt::from_bytes(ALL_TAGS_GZIP, Compression::Gzip).unwrap();
+	assert_eq!(name, "Level");
+
+	let level = tag.as_compound().unwrap();
+	assert_eq!(field(level, "longTest").as_long(), Some(i64::MAX));
+	assert_eq!(field(level, "shortTest").as_short(), Some(i16::MAX));
+	assert_eq!(field(level, "intTest").as_int(), Some(i32::MAX));
+	assert_eq!(field(level, "byteTest").as_byte(), Some(127));
+	assert_eq!(field(level, "floatTest").as_float(), Some(0.498_231_47));
+	assert_eq!(
+		field(level, "doubleTest").as_double(),
+		Some(0.493_128_713_218_231_5)
+	);
+	assert_eq!(
+		field(level, "stringTest").as_str(),
+		Some("HELLO WORLD THIS IS A TEST STRING ÅÄÖ!")
+	);
+
+	let nested = field(level, "nested compound test").as_compound().unwrap();
+	let ham = field(nested, "ham").as_compound().unwrap();
+	assert_eq!(field(ham, "name").as_str(), Some("Hampus"));
+	assert_eq!(field(ham, "value").as_float(), Some(0.75));
+
+	let longs = field(level, "listTest (long)").as_list().unwrap();
+	assert_eq!(
+		longs
+			.iter()
+			.map(|tag| tag.as_long().unwrap())
+			.collect::<Vec<_>>(),
+		[11, 12, 13, 14, 15]
+	);
+
+	let compounds = field(level, "listTest (compound)").as_list().unwrap();
+	assert_eq!(compounds.len(), 2);
+	let second = compounds[1].as_compound().unwrap();
+	assert_eq!(field(second, "name").as_str(), Some("Compound tag #1"));
+	assert_eq!(field(second, "created-on").as_long(), Some(1264099775885));
+
+	let (_, bytes) = level
+		.iter()
+		.find(|(name, _)| name.starts_with("byteArrayTest"))
+		.unwrap();
+	let bytes = bytes.as_byte_array().unwrap();
+	assert_eq!(bytes.len(), 1000);
+	for (n, byte) in bytes.iter().enumerate() {
+		assert_eq!(*byte as usize, (n * n * 255 + n * 7) % 100);
+	}
+}
+
+#[test]
+fn round_trips_empty_and_nested_lists() {
+	let tag = Tag::Compound(
+		Compound::new()
+			.with("emptyList", Tag::List(nbt::TAG_BYTE, vec![]))
+			.with(
+				"nestedList",
+				Tag::List(
+					nbt::TAG_LIST,
+					vec![
+						Tag::List(
+							nbt::TAG_SHORT,
+							vec![Tag::Short(1), Tag::Short(-1)],
+						),
+						Tag::List(
+							nbt::TAG_STRING,
+							vec![Tag::String("a".into())],
+						),
+					],
+				),
+			),
+	);
+	let bytes = nbt::to_bytes("lists", &tag, Compression::None).unwrap();
+
+	assert_eq!(
+		nbt::from_bytes(&bytes, Compression::None).unwrap(),
+		("lists".into(), tag)
+	);
+}
+
+#[test]
+fn does_not_trust_lengths_for_allocation() {
+	let mut bytes = vec![nbt::TAG_BYTE_ARRAY, 0, 0, 0x7F, 0xFF, 0xFF, 0xFF];
+	bytes.extend_from_slice(&[1, 2, 3]);
+	assert!(nbt::from_bytes(&bytes, Compression::None).is_err());
+
+	let bytes = [nbt::TAG_LIST, 0, 0, nbt::TAG_LIST, 0x7F, 0xFF, 0xFF, 0xFF];
+	assert!(nbt::from_bytes(&bytes, Compression::None).is_err());
+}
+
+#[test]
+fn limits_how_far_payloads_inflate() {
+	let tag = Tag::ByteArray(vec![0; nbt::MAX_DECOMPRESSED_SIZE]);
+	let gzip = nbt::to_bytes("", &tag, Compression::Gzip).unwrap();
+	let zlib = nbt::to_bytes("", &tag, Compression::Zlib).unwrap();
+
+	assert!(matches!(
+		nbt::from_bytes(&gzip, Compression::Gzip),
+		Err(Error::InvalidGzip)
+	));
+	assert!(matches!(
+		nbt::from_bytes(&zlib, Compression::Zlib),
+		Err(Error::InvalidZlib)
+	));
+}
+
+#[test]
+fn gzip_and_zlib_files_hold_the_same_tag() {
+	let gzip = nbt::from_bytes(ALL_TAGS_GZIP, Compression::Gzip).unwrap();
+	let zlib = nbt::from_bytes(ALL_TAGS_ZLIB, Compression::Zlib).unwrap();
+
+	assert_eq!(gzip, zlib);
+}
+
+#[test]
+fn detects_compression() {
+	assert_eq!(Compression::detect(HELLO_WORLD), Compression::None);
+	assert_eq!(Compression::detect(ALL_TAGS_GZIP), Compression::Gzip);
+	assert_eq!(Compression::detect(ALL_TAGS_ZLIB), Compression::Zlib);
+}
+
+#[test]
+fn round_trips_through_every_compression() {
+	let (name, tag) =
+		nbt::from_bytes(ALL_TAGS_GZIP, Compression::Gzip).unwrap();
+	let raw = nbt::to_bytes(&name, &tag, Compression::None).unwrap();
+
+	for compression in [Compression::None, Compression::Gzip, Compression::Zlib]
+	{
+		let bytes = nbt::to_bytes(&name, &tag, compression).unwrap();
+		let (read_name, read_tag) =
+			nbt::from_bytes(&bytes, compression).unwrap();
+
+		assert_eq!(read_name, name);
+		assert_eq!(read_tag, tag);
+		assert_eq!(
+			nbt::to_bytes(&read_name, &read_tag, Compression::None).unwrap(),
+			raw
+		);
+	}
+}
+
+#[test]
+fn rejects_corrupt_input() {
+	assert!(matches!(
+		nbt::from_bytes(&[0x0C, 0x00, 0x00], Compression::None),
+		Err(Error::InvalidTag { id: 0x0C })
+	));
+	assert!(nbt::from_bytes(&HELLO_WORLD[..20], Compression::None).is_err());
+
+	let mut corrupt = ALL_TAGS_GZIP.to_vec();
+	let crc = corrupt.len() - 8;
+	corrupt[crc] ^= 0xFF;
+	assert!(matches!(
+		nbt::from_bytes(&corrupt, Compression::Gzip),
+		Err(Error::InvalidGzip)
+	));
+}
+
+#[test]
+fn rejects_deeply_nested_lists() {
+	let mut bytes = vec![nbt::TAG_LIST, 0, 0];
+	for _ in 0..=nbt::MAX_DEPTH {
+		bytes.extend_from_slice(&[nbt::TAG_LIST, 0, 0, 0, 1]);
+	}
+	bytes.extend_from_slice(&[nbt::TAG_BYTE, 0, 0, 0, 0]);
+
+	assert!(matches!(
+		nbt::from_bytes(&bytes, Compression::None),
+		Err(Error::TagTooDeep)
+	));
+}
+
+#[test]
+fn round_trips_alpha_chunks_and_levels() {
+	let mut chunk = Chunk::new(-3, 70);
+	chunk.set_block(1, 64, 3, 7);
+	chunk.set_meta(1, 64, 3, 5);
+	chunk.set_sky_light(1, 65, 3, 15);
+	let bytes =
+		nbt::to_bytes("", &chunk.to_nbt(1234), Compression::Gzip).unwrap();
+	let (_, tag) = nbt::from_bytes(&bytes, Compression::Gzip).unwrap();
+	assert_eq!(Chunk::from_nbt(&tag).unwrap(), chunk);
+
+	let level = LevelData {
+		seed: -42,
+		spawn_x: 8,
+		spawn_y: 70,
+		spawn_z: -8,
+		time: 24000,
+		last_played: 1_000_000,
+		size_on_disk: 4096,
+	};
+	let bytes = nbt::to_bytes("", &level.to_nbt(), Compression::Gzip).unwrap();
+	let (_, tag) = nbt::from_bytes(&bytes, Compression::Gzip).unwrap();
+	assert_eq!(LevelData::from_nbt(&tag).unwrap(), level);
+}
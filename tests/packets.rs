@@ -0,0 +1,409 @@
+use oxidized_alpha::{
+	bytes::{ByteReader, ByteWriter},
+	packets::{
+		self, to_angle, to_fixed_point, ClientboundPacket, Packet,
+		ServerboundPacket,
+	},
+	Error, ItemStack,
+};
+use std::collections::BTreeSet;
+
+#[test]
+fn encodes_positions_in_thirty_seconds_of_a_block() {
+	assert_eq!(to_fixed_point(0.0), 0);
+	assert_eq!(to_fixed_point(1.5), 48);
+	assert_eq!(to_fixed_point(64.99), 2079);
+	assert_eq!(to_fixed_point(-0.01), -1);
+	assert_eq!(to_fixed_point(-2.5), -80);
+}
+
+#[test]
+fn encodes_angles_as_wrapping_bytes() {
+	assert_eq!(to_angle(0.0), 0);
+	assert_eq!(to_angle(90.0), 64);
+	assert_eq!(to_angle(180.0), -128);
+	assert_eq!(to_angle(-90.0), -64);
+	assert_eq!(to_angle(450.0), 64);
+	assert_eq!(to_angle(-1.0), 0);
+}
+
+#[test]
+fn rejects_negative_lengths() {
+	let complex_entity =
+		[0x3b, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0x01, 0x02];
+	let inventory = [0x05, 0xff, 0xff, 0xff, 0xff, 0x80, 0x00];
+
+	for packet in [&complex_entity[..], &inventory] {
+		let mut reader = ByteReader::new(packet);
+		assert!(ServerboundPacket::decode(&mut reader).is_err());
+	}
+}
+
+#[test]
+fn rejects_lengths_too_long_to_send() {
+	let packet = ClientboundPacket::ChatMessage {
+		message: "a".repeat(u16::MAX as usize + 1),
+	};
+
+	assert!(matches!(
+		packet.encode(&mut ByteWriter::new()),
+		Err(Error::InvalidLength { length: 65536 })
+	));
+}
+
+fn serverbound_packets() -> Vec<ServerboundPacket> {
+	let items = vec![
+		Some(ItemStack {
+			id: 1,
+			count: 64,
+			damage: 0,
+		}),
+		None,
+		Some(ItemStack {
+			id: 257,
+			count: 1,
+			damage: 12,
+		}),
+	];
+
+	vec![
+		ServerboundPacket::KeepAlive,
+		ServerboundPacket::Login {
+			protocol_version: 6,
+			username: "steve".into(),
+			password: "Password".into(),
+			map_seed: -42,
+			dimension: -1,
+		},
+		ServerboundPacket::Handshake {
+			username: "steve".into(),
+		},
+		ServerboundPacket::ChatMessage {
+			message: "hello ÅÄÖ".into(),
+		},
+		ServerboundPacket::PlayerInventory { kind: -1, items },
+		ServerboundPacket::UseEntity {
+			user: 1,
+			target: 2,
+			left_click: true,
+		},
+		ServerboundPacket::Respawn,
+		ServerboundPacket::Player { on_ground: true },
+		ServerboundPacket::PlayerPosition {
+			x: 0.5,
+			y: 64.0,
+			stance: 65.62,
+			z: -3.25,
+			on_ground: false,
+		},
+		ServerboundPacket::PlayerLook {
+			yaw: 90.0,
+			pitch: -45.5,
+			on_ground: true,
+		},
+		ServerboundPacket::PlayerPositionAndLook {
+			x: 0.5,
+			y: 64.0,
+			stance: 65.62,
+			z: -3.25,
+			yaw: 90.0,
+			pitch: -45.5,
+			on_ground: true,
+		},
+		ServerboundPacket::PlayerDigging {
+			status: 2,
+			x: -5,
+			y: 64,
+			z: 7,
+			face: 1,
+		},
+		ServerboundPacket::PlayerBlockPlacement {
+			item_id: 4,
+			x: -5,
+			y: 64,
+			z: 7,
+			direction: 1,
+		},
+		ServerboundPacket::HoldingChange {
+			entity_id: 0,
+			item_id: 4,
+		},
+		ServerboundPacket::Animation {
+			entity_id: 3,
+			animation: 1,
+		},
+		ServerboundPacket::PickupSpawn {
+			entity_id: 3,
+			item_id: 4,
+			count: 2,
+			x: 16,
+			y: 2048,
+			z: -16,
+			rotation: 1,
+			pitch: -2,
+			roll: 3,
+		},
+		ServerboundPacket::ComplexEntity {
+			x: 1,
+			y: 64,
+			z: 2,
+			payload: vec![1, 2, 3],
+		},
+		ServerboundPacket::KickOrDisconnect {
+			reason: "Quitting".into(),
+		},
+	]
+}
+
+fn clientbound_packets() -> Vec<ClientboundPacket> {
+	vec![
+		ClientboundPacket::KeepAlive,
+		ClientboundPacket::Login {
+			entity_id: 7,
+			server_name: String::new(),
+			motd: "A Minecraft Server".into(),
+			map_seed: -42,
+			dimension: 0,
+		},
+		ClientboundPacket::Handshake {
+			connection_hash: "-".into(),
+		},
+		ClientboundPacket::ChatMessage {
+			message: "steve joined the game.".into(),
+		},
+		ClientboundPacket::TimeUpdate { time: 24000 },
+		ClientboundPacket::PlayerInventory {
+			kind: -2,
+			items: vec![
+				None,
+				Some(ItemStack {
+					id: 307,
+					count: 1,
+					damage: 5,
+				}),
+				None,
+				None,
+			],
+		},
+		ClientboundPacket::SpawnPosition { x: 0, y: 64, z: 0 },
+		ClientboundPacket::UpdateHealth { health: 20 },
+		ClientboundPacket::Respawn,
+		ClientboundPacket::PlayerPositionAndLook {
+			x: 0.5,
+			stance: 65.62,
+			y: 64.0,
+			z: -3.25,
+			yaw: 90.0,
+			pitch: -45.5,
+			on_ground: true,
+		},
+		ClientboundPacket::HoldingChange {
+			entity_id: 7,
+			item_id: 4,
+		},
+		ClientboundPacket::AddToInventory {
+			item: ItemStack {
+				id: 4,
+				count: 3,
+				damage: 0,
+			},
+		},
+		ClientboundPacket::Animation {
+			entity_id: 7,
+			animation: 1,
+		},
+		ClientboundPacket::NamedEntitySpawn {
+			entity_id: 7,
+			name: "steve".into(),
+			x: 16,
+			y: 2048,
+			z: -16,
+			rotation: 64,
+			pitch: -10,
+			current_item: 0,
+		},
+		ClientboundPacket::PickupSpawn {
+			entity_id: 8,
+			item_id: 4,
+			count: 2,
+			x: 16,
+			y: 2048,
+			z: -16,
+			rotation: 1,
+			pitch: -2,
+			roll: 3,
+		},
+		ClientboundPacket::CollectItem {
+			collected_entity_id: 8,
+			collector_entity_id: 7,
+		},
+		ClientboundPacket::AddObjectOrVehicle {
+			entity_id: 9,
+			kind: 10,
+			x: 16,
+			y: 2048,
+			z: -16,
+		},
+		ClientboundPacket::MobSpawn {
+			entity_id: 10,
+			kind: 90,
+			x: 16,
+			y: 2048,
+			z: -16,
+			yaw: 5,
+			pitch: -5,
+		},
+		ClientboundPacket::EntityVelocity {
+			entity_id: 8,
+			velocity_x: 100,
+			velocity_y: -200,
+			velocity_z: 300,
+		},
+		ClientboundPacket::DestroyEntity { entity_id: 8 },
+		ClientboundPacket::Entity { entity_id: 7 },
+		ClientboundPacket::EntityRelativeMove {
+			entity_id: 7,
+			dx: 1,
+			dy: -2,
+			dz: 3,
+		},
+		ClientboundPacket::EntityLook {
+			entity_id: 7,
+			yaw: 64,
+			pitch: -10,
+		},
+		ClientboundPacket::EntityLookAndRelativeMove {
+			entity_id: 7,
+			dx: 1,
+			dy: -2,
+			dz: 3,
+			yaw: 64,
+			pitch: -10,
+		},
+		ClientboundPacket::EntityTeleport {
+			entity_id: 7,
+			x: 16,
+			y: 2048,
+			z: -16,
+			yaw: 64,
+			pitch: -10,
+		},
+		ClientboundPacket::EntityStatus {
+			entity_id: 7,
+			status: 2,
+		},
+		ClientboundPacket::PreChunk {
+			x: -1,
+			z: 2,
+			load: true,
+		},
+		ClientboundPacket::MapChunk {
+			x: -16,
+			y: 0,
+			z: 32,
+			size_x: 15,
+			size_y: 127,
+			size_z: 15,
+			compressed_data: vec![0x78, 0x9c, 3, 0],
+		},
+		ClientboundPacket::MultiBlockChange {
+			chunk_x: -1,
+			chunk_z: 2,
+			coordinates: vec![0x1040, 0x0203],
+			block_types: vec![1, 4],
+			metadata: vec![0, 2],
+		},
+		ClientboundPacket::BlockChange {
+			x: -5,
+			y: 64,
+			z: 7,
+			block_type: 4,
+			metadata: 0,
+		},
+		ClientboundPacket::ComplexEntity {
+			x: 1,
+			y: 64,
+			z: 2,
+			payload: vec![1, 2, 3],
+		},
+		ClientboundPacket::Explosion {
+			x: 0.5,
+			y: 64.0,
+			z: -3.25,
+			radius: 3.0,
+			records: vec![(0, 1, -1), (2, -3, 4)],
+		},
+		ClientboundPacket::KickOrDisconnect {
+			reason: "Server closed".into(),
+		},
+	]
+}
+
+#[test]
+fn round_trips_every_packet() {
+	let packets = serverbound_packets()
+		.into_iter()
+		.map(Packet::from)
+		.chain(clientbound_packets().into_iter().map(Packet::from));
+
+	let mut ids = BTreeSet::new();
+	for packet in packets {
+		let mut writer = ByteWriter::new();
+		packet.encode(&mut writer).unwrap();
+		assert_eq!(writer.as_bytes()[0], packet.id());
+
+		let mut reader = ByteReader::new(writer.as_bytes());
+		let decoded = match packet {
+			Packet::Serverbound(_) => Packet::decode_serverbound(&mut reader),
+			Packet::Clientbound(_) => Packet::decode_clientbound(&mut reader),
+		};
+		assert_eq!(decoded.unwrap(), packet);
+		assert!(reader.remaining().is_empty(), "{packet:?}");
+		ids.insert(packet.id());
+	}
+
+	assert_eq!(
+		ids,
+		BTreeSet::from([
+			packets::KEEP_ALIVE,
+			packets::LOGIN,
+			packets::HANDSHAKE,
+			packets::CHAT_MESSAGE,
+			packets::TIME_UPDATE,
+			packets::PLAYER_INVENTORY,
+			packets::SPAWN_POSITION,
+			packets::USE_ENTITY,
+			packets::UPDATE_HEALTH,
+			packets::RESPAWN,
+			packets::PLAYER,
+			packets::PLAYER_POSITION,
+			packets::PLAYER_LOOK,
+			packets::PLAYER_POSITION_AND_LOOK,
+			packets::PLAYER_DIGGING,
+			packets::PLAYER_BLOCK_PLACEMENT,
+			packets::HOLDING_CHANGE,
+			packets::ADD_TO_INVENTORY,
+			packets::ANIMATION,
+			packets::NAMED_ENTITY_SPAWN,
+			packets::PICKUP_SPAWN,
+			packets::COLLECT_ITEM,
+			packets::ADD_OBJECT_OR_VEHICLE,
+			packets::MOB_SPAWN,
+			packets::ENTITY_VELOCITY,
+			packets::DESTROY_ENTITY,
+			packets::ENTITY,
+			packets::ENTITY_RELATIVE_MOVE,
+			packets::ENTITY_LOOK,
+			packets::ENTITY_LOOK_AND_RELATIVE_MOVE,
+			packets::ENTITY_TELEPORT,
+			packets::ENTITY_STATUS,
+			packets::PRE_CHUNK,
+			packets::MAP_CHUNK,
+			packets::MULTI_BLOCK_CHANGE,
+			packets::BLOCK_CHANGE,
+			packets::COMPLEX_ENTITY,
+			packets::EXPLOSION,
+			packets::KICK_OR_DISCONNECT,
+		])
+	);
+}
@@ -0,0 +1,109 @@
+use oxidized_alpha::{
+	inventory::Section,
+	nbt::{Compound, Tag},
+	Health, Inventory, ItemStack, Player,
+};
+
+fn player_at(x: f64, y: f64, z: f64) -> Player {
+	Player {
+		username: "steve".into(),
+		logged_in: true,
+		x,
+		y,
+		z,
+		yaw: 0.0,
+		pitch: 0.0,
+		stance: y + 1.62,
+		on_ground: true,
+		inventory: Inventory::default(),
+		held_item: 0,
+		health: Health::default(),
+	}
+}
+
+#[test]
+fn intersects_the_blocks_at_its_feet_and_head() {
+	let player = player_at(0.5, 64.0, -0.5);
+
+	assert_eq!(player.chunk_position(), (0, -1));
+	assert!(player.intersects_block(0, 64, -1));
+	assert!(player.intersects_block(0, 65, -1));
+	assert!(!player.intersects_block(0, 63, -1));
+	assert!(!player.intersects_block(0, 66, -1));
+	assert!(!player.intersects_block(1, 64, -1));
+}
+
+#[test]
+fn intersects_neighbouring_blocks_near_an_edge() {
+	let player = player_at(0.9, 64.5, 0.5);
+
+	assert!(player.intersects_block(1, 64, 0));
+	assert!(player.intersects_block(0, 66, 0));
+	assert!(!player.intersects_block(-1, 64, 0));
+	assert!(!player.intersects_block(1, 64, 1));
+}
+
+#[test]
+fn round_trips_through_a_player_file() {
+	let mut player = player_at(10.5, 70.0, -3.25);
+	player.yaw = 90.0;
+	player.health.health = 7;
+	player.health.air = 120;
+	player.inventory.add(ItemStack {
+		id: 1,
+		count: 12,
+		damage: 0,
+	});
+	player
+		.inventory
+		.replace(
+			-2,
+			&[
+				Some(ItemStack {
+					id: 310,
+					count: 1,
+					damage: 40,
+				}),
+				None,
+				None,
+				None,
+			],
+		)
+		.unwrap();
+
+	let mut loaded = player_at(0.5, 64.0, 0.5);
+	loaded.load_nbt(&player.to_nbt()).unwrap();
+	assert_eq!(loaded, player);
+}
+
+#[test]
+fn leaves_out_stacks_in_unknown_slots() {
+	let stack = |slot: i8| {
+		Tag::Compound(
+			Compound::new()
+				.with("Slot", Tag::Byte(slot))
+				.with("id", Tag::Short(1))
+				.with("Count", Tag::Byte(1))
+				.with("Damage", Tag::Short(0)),
+		)
+	};
+	let inventory =
+		Inventory::from_nbt(&Tag::list(vec![stack(3), stack(50), stack(82)]));
+
+	let one = Some(ItemStack {
+		id: 1,
+		count: 1,
+		damage: 0,
+	});
+	let main = inventory.section(Section::Main);
+	assert_eq!(main[3], one);
+	assert_eq!(main.iter().flatten().count(), 1);
+	assert_eq!(
+		inventory.section(Section::Crafting),
+		[None, None, one, None]
+	);
+	assert!(inventory
+		.section(Section::Armor)
+		.iter()
+		.all(Option::is_none));
+}